@@ -0,0 +1,120 @@
+// Renders a MIDI file through the synth to a WAV file, without a host.
+//
+//     render --midi song.mid --out bass.wav [--preset bass.txt] [--rate 44100]
+//            [--format 16|24|float] [--tail 2.0] [--seed 1]
+//            [--scl scale.scl [--kbm mapping.kbm]]
+extern crate basicvst;
+extern crate vst;
+
+use basicvst::render::midi_file::MidiFile;
+use basicvst::render::player::Player;
+use basicvst::render::preset;
+use basicvst::render::wav::{self, SampleFormat};
+use basicvst::Sh101;
+use std::env;
+use std::fs;
+use std::process;
+use vst::plugin::Plugin;
+
+struct Options {
+    midi: String,
+    out: String,
+    preset: Option<String>,
+    rate: u32,
+    format: SampleFormat,
+    tail: f64, // seconds rendered after the last event
+    seed: Option<u64>,
+    scl: Option<String>,
+    kbm: Option<String>,
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: render --midi FILE --out FILE [--preset FILE] [--rate HZ] \
+         [--format 16|24|float] [--tail SECONDS] [--seed N] [--scl FILE [--kbm FILE]]"
+    );
+    process::exit(2);
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("render: {}", message);
+    process::exit(1);
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        midi: String::new(),
+        out: String::new(),
+        preset: None,
+        rate: 44100,
+        format: SampleFormat::PCM_16,
+        tail: 2.0,
+        seed: None,
+        scl: None,
+        kbm: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        match flag.as_str() {
+            "--midi" => options.midi = value,
+            "--out" => options.out = value,
+            "--preset" => options.preset = Some(value),
+            "--rate" => options.rate = value.parse().unwrap_or_else(|_| usage()),
+            "--format" => {
+                options.format = match value.as_str() {
+                    "16" => SampleFormat::PCM_16,
+                    "24" => SampleFormat::PCM_24,
+                    "float" => SampleFormat::FLOAT_32,
+                    _ => usage(),
+                }
+            }
+            "--tail" => options.tail = value.parse().unwrap_or_else(|_| usage()),
+            "--seed" => options.seed = Some(value.parse().unwrap_or_else(|_| usage())),
+            "--scl" => options.scl = Some(value),
+            "--kbm" => options.kbm = Some(value),
+            _ => usage(),
+        }
+    }
+    if options.midi.is_empty() || options.out.is_empty() || options.rate == 0 {
+        usage();
+    }
+    options
+}
+
+fn main() {
+    let options = parse_options();
+
+    let file = MidiFile::load(&options.midi)
+        .unwrap_or_else(|err| fail(format!("{}: {}", options.midi, err)));
+
+    let mut synth = Sh101::standalone();
+    synth.set_offline(true);
+    synth.set_sample_rate(options.rate as f32);
+    if let Some(path) = &options.preset {
+        let text =
+            fs::read_to_string(path).unwrap_or_else(|err| fail(format!("{}: {}", path, err)));
+        let params = synth.get_parameter_object();
+        let num_parameters = synth.get_info().parameters;
+        preset::apply(params.as_ref(), num_parameters, &text)
+            .unwrap_or_else(|err| fail(format!("{}: {}", path, err)));
+    }
+    if let Some(scl) = &options.scl {
+        synth
+            .load_tuning(scl, options.kbm.as_ref().map(String::as_str))
+            .unwrap_or_else(|err| fail(format!("{}: {}", scl, err)));
+    }
+    if let Some(seed) = options.seed {
+        synth.set_seed(seed);
+    }
+
+    let mut player = Player::new(&file, options.rate as f32);
+    let length = player.length() + (options.tail * f64::from(options.rate)) as usize;
+    let mut left = vec![0.0; length];
+    let mut right = vec![0.0; length];
+    player.render(&mut synth, &mut left, &mut right);
+
+    wav::write(&options.out, options.format, options.rate, &[&left, &right])
+        .unwrap_or_else(|err| fail(format!("{}: {}", options.out, err)));
+}
@@ -4,89 +4,388 @@ extern crate vst_gui;
 
 mod logics;
 mod parameters;
+pub mod render;
 
 use crate::vst::host::Host;
-use vst::api::{Events, Supported};
+use vst::api::{Events, Supported, TimeInfoFlags};
 use vst::buffer::AudioBuffer;
 use vst::event::Event;
 use vst::plugin::HostCallback;
-use vst::plugin::{Category, Info, Plugin, PluginParameters};
+use vst::plugin::{CanDo, Category, Info, Plugin, PluginParameters};
 
+use logics::amplifier::Amplifier;
+use logics::analog::Analog;
+use logics::bit_crusher::BitCrusher;
+use logics::drive::Drive;
 use logics::envelope::ADSR;
+use logics::external_input::{ExternalInput, TriggerMode};
+use logics::fx::FX;
+use logics::glide::Glide;
+use logics::lfo::LFO;
+use logics::mod_matrix::{ModMatrix, ModSources};
+use logics::output::Output;
+use logics::oversampler::{factor_from_f32, Oversampler};
+use logics::tuning::scala::{KeyboardMapping, ScalaError, Scale};
+use logics::tuning::Tuning;
+use logics::unison::{Spread, Unison};
 use logics::vcf::VCF;
-use logics::vco::VCO;
+use parameters::amplifier_param::AmplifierParam;
+use parameters::analog_param::AnalogParam;
+use parameters::drive_param::{BitCrusherParam, DriveParam};
 use parameters::envelope_param::EnvelopeParam;
+use parameters::external_input_param::ExternalInputParam;
+use parameters::fx_param::FXParam;
+use parameters::lfo_param::LFOParam;
+use parameters::mod_matrix_param::ModMatrixParam;
+use parameters::output_param::OutputParam;
+use parameters::oversampling_param::OversamplingParam;
+use parameters::performance_param::PerformanceParam;
 use parameters::sh101_param::SH101Param;
+use parameters::tuning_param::TuningParam;
+use parameters::unison_param::UnisonParam;
 use parameters::vcf_param::VCFParam;
 use parameters::vco_param::VCOParam;
 
 use std::sync::Arc;
 
-fn midi_pitch_to_freq(pitch: u8) -> f32 {
-    const A4_PITCH: i8 = 69;
-    const A4_FREQ: f32 = 440.0;
-
-    ((f32::from(pitch as i8 - A4_PITCH)) / 12.).exp2() * A4_FREQ
-}
-
-struct Sh101 {
+pub struct Sh101 {
+    host: Option<HostCallback>,
     params: Arc<SH101Param>,
     vca: ADSR,
-    vco: VCO,
+    amplifier: Amplifier,
+    vco: Unison,
     vcf: VCF,
+    spread: Spread,
+    drive: Drive,
+    bit_crusher: BitCrusher,
     modulation: ADSR,
+    external: ExternalInput,
+    oversampler: Oversampler,
+    output: Output,
+    fx: FX,
+    glide: Glide,
+    tuning: Tuning,
+    analog: Analog,
+    lfo: LFO,
+    mod_matrix: ModMatrix,
+    velocity: f32,
+    mod_wheel: f32,
+    aftertouch: f32,
     note: u8,
+    gate: bool,
+    external_gate: bool,
+    accent: f32,
+    cutoff_variance: f32, // octaves, drawn per note by the analog emulation
+    sample_rate: f32,
+    offline: bool,
 }
 
 impl Sh101 {
-    fn process_midi_event(&mut self, data: [u8; 3]) {
-        match data[0] {
-            128 => self.note_off(data[1]),
-            144 => self.note_on(data[1]),
-            _ => (),
-        }
-    }
-
-    fn note_on(&mut self, note: u8) {
-        if note != self.note {
-            self.vca.retrigger();
-            self.modulation.retrigger();
-        }
-        self.note = note;
-        self.vca.gate_on();
-        self.modulation.gate_on();
-    }
-
-    fn note_off(&mut self, note: u8) {
-        if note == self.note {
-            self.vca.gate_off();
-            self.modulation.gate_off();
-        }
-    }
-}
-
-impl Plugin for Sh101 {
-    fn new(_host: HostCallback) -> Self {
+    fn with_host(host: Option<HostCallback>) -> Self {
         let vca_param = Arc::new(EnvelopeParam::default());
         let vco_param = Arc::new(VCOParam::default());
         let vcf_param = Arc::new(VCFParam::default());
         let mod_param = Arc::new(EnvelopeParam::default());
+        let oversampling_param = Arc::new(OversamplingParam::default());
+        let amp_param = Arc::new(AmplifierParam::default());
+        let performance_param = Arc::new(PerformanceParam::default());
+        let lfo_param = Arc::new(LFOParam::default());
+        let mod_matrix_param = Arc::new(ModMatrixParam::default());
+        let output_param = Arc::new(OutputParam::default());
+        let unison_param = Arc::new(UnisonParam::default());
+        let external_input_param = Arc::new(ExternalInputParam::default());
+        let fx_param = Arc::new(FXParam::default());
+        let drive_param = Arc::new(DriveParam::default());
+        let bit_crusher_param = Arc::new(BitCrusherParam::default());
+        let tuning_param = Arc::new(TuningParam::default());
+        let analog_param = Arc::new(AnalogParam::default());
         let param = Arc::new(SH101Param::new(
             vco_param.clone(),
             vcf_param.clone(),
             vca_param.clone(),
             mod_param.clone(),
+            oversampling_param.clone(),
+            amp_param.clone(),
+            performance_param.clone(),
+            lfo_param.clone(),
+            mod_matrix_param.clone(),
+            output_param.clone(),
+            unison_param.clone(),
+            external_input_param.clone(),
+            fx_param.clone(),
+            drive_param.clone(),
+            bit_crusher_param.clone(),
+            tuning_param.clone(),
+            analog_param.clone(),
         ));
         Self {
+            host: host,
             params: param,
             vca: ADSR::new(vca_param.clone(), 44100.0),
-            vco: VCO::new(vco_param.clone(), 44100.0),
+            amplifier: Amplifier::new(amp_param.clone(), 44100.0),
+            vco: Unison::new(unison_param.clone(), vco_param.clone(), 44100.0),
             vcf: VCF::new(vcf_param.clone(), 44100.0),
+            spread: Spread::new(unison_param.clone(), 44100.0),
+            drive: Drive::new(drive_param.clone()),
+            bit_crusher: BitCrusher::new(bit_crusher_param.clone()),
             modulation: ADSR::new(mod_param.clone(), 44100.0),
+            external: ExternalInput::new(external_input_param.clone(), 44100.0),
+            oversampler: Oversampler::new(),
+            output: Output::new(output_param.clone()),
+            fx: FX::new(fx_param.clone(), 44100.0),
+            glide: Glide::new(44100.0),
+            tuning: Tuning::new(tuning_param.clone()),
+            analog: Analog::new(analog_param.clone(), 44100.0),
+            lfo: LFO::new(lfo_param.clone(), 44100.0),
+            mod_matrix: ModMatrix::new(mod_matrix_param.clone()),
+            velocity: 0.0,
+            mod_wheel: 0.0,
+            aftertouch: 0.0,
             note: 69,
+            gate: false,
+            external_gate: false,
+            accent: 0.0,
+            cutoff_variance: 0.0,
+            sample_rate: 44100.0,
+            offline: false,
+        }
+    }
+
+    // Builds the synth without a VST host, e.g. for offline rendering. Tempo
+    // then comes from set_tempo instead of the host.
+    pub fn standalone() -> Self {
+        Self::with_host(None)
+    }
+
+    pub fn process_sysex(&mut self, payload: &[u8]) {
+        self.tuning.process_sysex(payload);
+    }
+
+    pub fn process_midi_event(&mut self, data: [u8; 3]) {
+        match data[0] & 0xF0 {
+            128 => self.note_off(data[1]),
+            144 if data[2] == 0 => self.note_off(data[1]),
+            144 => self.note_on(data[1], data[2]),
+            176 if data[1] == 1 => self.mod_wheel = f32::from(data[2]) / 127.0,
+            208 => self.aftertouch = f32::from(data[1]) / 127.0,
+            _ => (),
+        }
+    }
+
+    // Velocities at or above the threshold are accented, and a note played while
+    // the previous one is still held slides into it.
+    fn note_on(&mut self, note: u8, velocity: u8) {
+        let threshold = self.params.performance_param.accent_threshold.get();
+        let accent = f32::from(velocity) / 127.0 >= threshold;
+        let slide = self.gate;
+        self.velocity = f32::from(velocity) / 127.0;
+        self.trigger(note, accent, slide);
+    }
+
+    // Entry point for sequencer steps, which carry their own accent and slide flags.
+    pub fn trigger(&mut self, note: u8, accent: bool, slide: bool) {
+        self.start_note(note, accent, slide);
+        self.gate = true;
+    }
+
+    // Opens the envelopes on `note` without claiming the MIDI gate, so the
+    // external gate can share it.
+    fn start_note(&mut self, note: u8, accent: bool, slide: bool) {
+        if slide {
+            let time = self.params.performance_param.slide_time.get();
+            self.glide.slide(f32::from(note), time);
+        } else {
+            if note != self.note {
+                self.vca.retrigger();
+                self.modulation.retrigger();
+            }
+            self.glide.jump(f32::from(note));
+
+            let variance = self.analog.note_on();
+            self.cutoff_variance = variance.cutoff;
+            self.vca.set_time_scale(variance.time_scale);
+            self.modulation.set_time_scale(variance.time_scale);
+            self.vco.note_on(variance.phase);
+        }
+        self.accent = if accent {
+            self.params.performance_param.accent_amount.get()
+        } else {
+            0.0
+        };
+        self.note = note;
+        self.vca.gate_on();
+        self.amplifier.gate_on();
+        self.modulation.gate_on();
+    }
+
+    fn note_off(&mut self, note: u8) {
+        if note == self.note && self.gate {
+            self.gate = false;
+            if !self.external_gate {
+                self.release();
+            }
         }
     }
 
+    // The external gate holds the current note, and only releases it once no
+    // MIDI note is holding it either.
+    fn set_external_gate(&mut self, open: bool) {
+        self.external_gate = open;
+        if !self.gate {
+            if open {
+                self.start_note(self.note, false, false);
+            } else {
+                self.release();
+            }
+        }
+    }
+
+    fn release(&mut self) {
+        self.vca.gate_off();
+        self.amplifier.gate_off();
+        self.modulation.gate_off();
+    }
+
+    // Retunes to a Scala scale, on the standard keyboard mapping unless a .kbm
+    // file is given.
+    pub fn load_tuning(&mut self, scl: &str, kbm: Option<&str>) -> Result<(), ScalaError> {
+        let scale = Scale::load(scl)?;
+        let mapping = match kbm {
+            Some(path) => KeyboardMapping::load(path)?,
+            None => KeyboardMapping::linear(scale.len()),
+        };
+        self.tuning.set_scale(&scale, &mapping);
+        Ok(())
+    }
+
+    // Seeds every random source, so renders with the same seed are identical.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.analog.seed(seed);
+        self.lfo.seed(seed.wrapping_add(1));
+        self.vco.seed(seed.wrapping_add(2));
+    }
+
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    pub fn process_block(
+        &mut self,
+        left_in: &[f32],
+        right_in: &[f32],
+        left_out: &mut [f32],
+        right_out: &mut [f32],
+    ) {
+        let stereo_in = left_in.iter().zip(right_in.iter());
+        let stereo_out = left_out.iter_mut().zip(right_out.iter_mut());
+
+        self.update_oversampling();
+        self.update_tempo();
+
+        for ((left_in, right_in), (left_out, right_out)) in stereo_in.zip(stereo_out) {
+            let input = self.external.tick(*left_in, *right_in);
+            if let TriggerMode::GATE = self.external.mode() {
+                if let Some(open) = self.external.gate_edge() {
+                    self.set_external_gate(open);
+                }
+            } else if self.external_gate {
+                self.set_external_gate(false);
+            }
+
+            let note = self.glide.tick();
+            let mut vca_env = self.vca.tick();
+            let mut moduletion = self.modulation.tick();
+            if let TriggerMode::FOLLOWER = self.external.mode() {
+                vca_env = self.external.envelope();
+                moduletion = vca_env;
+            }
+            let lfo = self.lfo.tick();
+            let destinations = self.mod_matrix.process(&ModSources {
+                vca_envelope: vca_env,
+                mod_envelope: moduletion,
+                lfo: lfo,
+                velocity: self.velocity,
+                mod_wheel: self.mod_wheel,
+                aftertouch: self.aftertouch,
+                keyboard: (note - 60.0) / 64.0,
+            });
+
+            let drift = self.analog.tick();
+            let pitch = self.tuning.freq(note + destinations.pitch + drift);
+            let env =
+                (self.amplifier.tick(vca_env) + destinations.vca).max(0.0) * (1.0 + self.accent);
+
+            self.vco.mod_pw(moduletion, lfo, destinations.pulse_width);
+            self.vco.mod_levels(
+                destinations.saw_level,
+                destinations.tri_level,
+                destinations.squ_level,
+                destinations.sub_level,
+                destinations.noise_level,
+            );
+            let cutoff = destinations.cutoff + self.cutoff_variance;
+            self.vcf.modulate(cutoff, destinations.resonance);
+            self.vcf.mod_fc(moduletion * (1.0 + self.accent), pitch);
+
+            let vco = &mut self.vco;
+            let vcf = &mut self.vcf;
+            let drive = &self.drive;
+            let filtered = self.oversampler.process(input, |x| {
+                drive.post(vcf.filter(drive.pre(vco.tick(pitch) + x)))
+            });
+            let (filtered_left, filtered_right) = self.spread.process(filtered);
+            let (filtered_left, filtered_right) =
+                self.bit_crusher.process(filtered_left, filtered_right);
+
+            let (left, right) = self
+                .output
+                .stereo(filtered_left * env, filtered_right * env);
+            let (left, right) = self.fx.process(left, right);
+            let (left, right) = self.output.process(left, right);
+
+            *left_out = left;
+            *right_out = right;
+        }
+    }
+
+    fn oversampling_factor(&self) -> usize {
+        let param = &self.params.oversampling_param;
+        if self.offline {
+            factor_from_f32(param.offline.get())
+        } else {
+            factor_from_f32(param.realtime.get())
+        }
+    }
+
+    // The delay follows the host tempo whenever the host reports one.
+    fn update_tempo(&mut self) {
+        let mask = TimeInfoFlags::TempoValid as i32;
+        if let Some(info) = self.host.as_ref().and_then(|host| host.get_time_info(mask)) {
+            if info.flags & mask != 0 {
+                self.fx.set_tempo(info.tempo as f32);
+            }
+        }
+    }
+
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.fx.set_tempo(bpm);
+    }
+
+    fn update_oversampling(&mut self) {
+        let factor = self.oversampling_factor();
+        if factor != self.oversampler.factor() {
+            self.oversampler.set_factor(factor);
+            self.vco.set_sample_rate(self.sample_rate * factor as f32);
+            self.vcf.set_sample_rate(self.sample_rate * factor as f32);
+        }
+    }
+}
+
+impl Plugin for Sh101 {
+    fn new(host: HostCallback) -> Self {
+        Self::with_host(Some(host))
+    }
+
     fn get_info(&self) -> Info {
         Info {
             name: "SH101".to_string(),
@@ -100,16 +399,39 @@ impl Plugin for Sh101 {
         }
     }
 
+    fn set_sample_rate(&mut self, rate: f32) {
+        let factor = self.oversampler.factor() as f32;
+        self.sample_rate = rate;
+        self.vca.set_sample_rate(rate);
+        self.amplifier.set_sample_rate(rate);
+        self.vco.set_sample_rate(rate * factor);
+        self.vcf.set_sample_rate(rate * factor);
+        self.spread.set_sample_rate(rate);
+        self.modulation.set_sample_rate(rate);
+        self.external.set_sample_rate(rate);
+        self.fx.set_sample_rate(rate);
+        self.glide.set_sample_rate(rate);
+        self.lfo.set_sample_rate(rate);
+        self.analog.set_sample_rate(rate);
+    }
+
+    fn can_do(&self, can_do: CanDo) -> Supported {
+        match can_do {
+            CanDo::ReceiveTimeInfo => Supported::Yes,
+            _ => Supported::Maybe,
+        }
+    }
+
     fn get_parameter_object(&mut self) -> Arc<dyn PluginParameters> {
         Arc::clone(&self.params) as Arc<dyn PluginParameters>
     }
 
     #[allow(unused_variables)]
-    #[allow(clippy::single_match)]
     fn process_events(&mut self, events: &Events) {
         for event in events.events() {
             match event {
                 Event::Midi(ev) => self.process_midi_event(ev.data),
+                Event::SysEx(ev) => self.process_sysex(ev.payload),
                 _ => (),
             }
         }
@@ -117,30 +439,61 @@ impl Plugin for Sh101 {
 
     fn process(&mut self, buffer: &mut AudioBuffer<f32>) {
         let (inputs, outputs) = buffer.split();
+        let (l_in, r_in) = inputs.split_at(1);
+        let (mut l_out, mut r_out) = outputs.split_at_mut(1);
+        self.process_block(&l_in[0], &r_in[0], &mut l_out[0], &mut r_out[0]);
+    }
+}
 
-        let (l, r) = inputs.split_at(1);
-        let stereo_in = l[0].iter().zip(r[0].iter());
+plugin_main!(Sh101);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let (mut l, mut r) = outputs.split_at_mut(1);
-        let stereo_out = l[0].iter_mut().zip(r[0].iter_mut());
+    const BLOCK: usize = 4410;
 
-        for ((_left_in, _right_in), (left_out, right_out)) in stereo_in.zip(stereo_out) {
-            let pitch = midi_pitch_to_freq(self.note);
-            let env = self.vca.tick();
-            let moduletion = self.modulation.tick();
+    // Renders `blocks` blocks with a constant external input and returns the
+    // RMS level of the last one.
+    fn render(synth: &mut Sh101, input: f32, blocks: usize) -> f32 {
+        let input = [input; BLOCK];
+        let mut left = [0.0; BLOCK];
+        let mut right = [0.0; BLOCK];
+        for _ in 0..blocks {
+            synth.process_block(&input, &input, &mut left, &mut right);
+        }
+        (left.iter().map(|x| x * x).sum::<f32>() / BLOCK as f32).sqrt()
+    }
 
-            self.vco.mod_pw(moduletion);
-            self.vcf.mod_fc(moduletion, pitch);
+    fn gated_synth() -> Sh101 {
+        let mut synth = Sh101::standalone();
+        synth.params.external_input_param.level.set(0.0);
+        synth.params.external_input_param.trigger.set(0.5);
+        synth
+    }
 
-            let signal = self.vco.tick(pitch);
-            let filtered_signal = self.vcf.filter(signal);
-            let amplitude_controlled = filtered_signal * env;
-            let out_sample = amplitude_controlled;
+    #[test]
+    fn external_gate_holds_the_note_after_midi_release() {
+        let mut synth = gated_synth();
+        synth.process_midi_event([0x90, 48, 100]);
+        render(&mut synth, 0.5, 2);
+        synth.process_midi_event([0x80, 48, 0]);
+        let held = render(&mut synth, 0.5, 10);
+        let released = render(&mut synth, 0.0, 40);
+        assert!(held > 0.01, "held at {}", held);
+        assert!(released < 0.0001, "released at {}", released);
+    }
 
-            *left_out = out_sample;
-            *right_out = out_sample;
-        }
+    #[test]
+    fn midi_gate_holds_the_note_after_external_release() {
+        let mut synth = gated_synth();
+        render(&mut synth, 0.5, 2);
+        synth.process_midi_event([0x90, 48, 100]);
+        render(&mut synth, 0.0, 2);
+        let held = render(&mut synth, 0.0, 10);
+        synth.process_midi_event([0x80, 48, 0]);
+        let released = render(&mut synth, 0.0, 40);
+        assert!(held > 0.01, "held at {}", held);
+        assert!(released < 0.0001, "released at {}", released);
     }
 }
-
-plugin_main!(Sh101);
@@ -0,0 +1,80 @@
+#[macro_use]
+use crate::parameters;
+use parameters::amplifier_param::AmplifierParam;
+use std::sync::Arc;
+
+const GATE_RAMP_TIME: f32 = 0.002; // seconds, long enough to avoid clicks
+
+pub enum AmplifierMode {
+    ENVELOPE,
+    GATE,
+}
+
+impl AmplifierMode {
+    pub fn from_f32(value: f32) -> Self {
+        if value < 0.5 {
+            Self::ENVELOPE
+        } else {
+            Self::GATE
+        }
+    }
+}
+
+pub struct Amplifier {
+    param: Arc<AmplifierParam>,
+    sample_rate: f32,
+    gate: bool,
+    gate_level: f32,
+}
+
+impl Amplifier {
+    pub fn new(param: Arc<AmplifierParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            sample_rate: sample_rate,
+            gate: false,
+            gate_level: 0.0,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(AmplifierParam::default()),
+            sample_rate: 44100.0,
+            gate: false,
+            gate_level: 0.0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn gate_on(&mut self) {
+        self.gate = true;
+    }
+
+    pub fn gate_off(&mut self) {
+        self.gate = false;
+    }
+
+    fn tick_gate(&mut self) -> f32 {
+        let step = 1.0 / (GATE_RAMP_TIME * self.sample_rate);
+        if self.gate {
+            self.gate_level = (self.gate_level + step).min(1.0);
+        } else {
+            self.gate_level = (self.gate_level - step).max(0.0);
+        }
+        self.gate_level
+    }
+
+    // Returns the gain for this sample given the VCA envelope output.
+    pub fn tick(&mut self, envelope: f32) -> f32 {
+        let gate = self.tick_gate();
+        let amplitude = match AmplifierMode::from_f32(self.param.mode.get()) {
+            AmplifierMode::ENVELOPE => envelope,
+            AmplifierMode::GATE => gate,
+        };
+        amplitude * self.param.level.get()
+    }
+}
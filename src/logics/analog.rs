@@ -0,0 +1,90 @@
+#[macro_use]
+use crate::parameters;
+use parameters::analog_param::AnalogParam;
+use rand::distributions::Standard;
+use rand::prelude::*;
+use std::sync::Arc;
+
+// Deviations at full amount.
+const MAX_DRIFT: f32 = 0.15; // semitones
+const DRIFT_RATE: f32 = 0.5; // Hz, how often the drift picks a new direction
+const MAX_CUTOFF_VARIANCE: f32 = 0.2; // octaves per note
+const MAX_TIME_TOLERANCE: f32 = 0.1; // fraction of each envelope stage time
+
+// Per-note deviations drawn on note-on.
+pub struct NoteVariance {
+    pub cutoff: f32,     // octaves
+    pub time_scale: f32, // envelope stage time multiplier
+    pub phase: f32,      // oscillator phase shift in cycles
+}
+
+// Component variance: a slow random pitch drift plus per-note deviations.
+// Everything comes from one RNG, so a fixed seed renders identically.
+pub struct Analog {
+    param: Arc<AnalogParam>,
+    sample_rate: f32,
+    rng: StdRng,
+    drift: f32,
+    drift_target: f32,
+    countdown: f32, // seconds until the drift picks a new target
+}
+
+impl Analog {
+    pub fn new(param: Arc<AnalogParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            sample_rate: sample_rate,
+            rng: StdRng::from_entropy(),
+            drift: 0.0,
+            drift_target: 0.0,
+            countdown: 0.0,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(AnalogParam::default()),
+            sample_rate: 44100.0,
+            rng: StdRng::from_entropy(),
+            drift: 0.0,
+            drift_target: 0.0,
+            countdown: 0.0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.drift = 0.0;
+        self.drift_target = 0.0;
+        self.countdown = 0.0;
+    }
+
+    fn bipolar(&mut self) -> f32 {
+        self.rng.sample::<f32, Standard>(Standard) * 2.0 - 1.0
+    }
+
+    pub fn note_on(&mut self) -> NoteVariance {
+        let amount = self.param.amount.get();
+        NoteVariance {
+            cutoff: self.bipolar() * MAX_CUTOFF_VARIANCE * amount,
+            time_scale: 1.0 + self.bipolar() * MAX_TIME_TOLERANCE * amount,
+            phase: self.rng.sample::<f32, Standard>(Standard) * amount,
+        }
+    }
+
+    // Returns the pitch drift for this sample in semitones.
+    pub fn tick(&mut self) -> f32 {
+        self.countdown -= 1.0 / self.sample_rate;
+        if self.countdown <= 0.0 {
+            self.countdown += 1.0 / DRIFT_RATE;
+            self.drift_target = self.bipolar();
+        }
+        let coef = 1.0 - (-DRIFT_RATE / self.sample_rate).exp();
+        self.drift += (self.drift_target - self.drift) * coef;
+        self.drift * MAX_DRIFT * self.param.amount.get()
+    }
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+use crate::parameters;
+use parameters::drive_param::BitCrusherParam;
+use std::sync::Arc;
+
+const MAX_BITS: f32 = 16.0;
+const MAX_REDUCTION: f32 = 64.0; // sample rate divisor at full rate reduction
+
+// Bit depth and sample rate reducer. It runs after decimation: the aliasing
+// of the reduced rate is the point, so it must not be filtered away.
+pub struct BitCrusher {
+    param: Arc<BitCrusherParam>,
+    phase: f32,
+    held: (f32, f32),
+}
+
+impl BitCrusher {
+    pub fn new(param: Arc<BitCrusherParam>) -> Self {
+        Self {
+            param: param,
+            phase: 0.0,
+            held: (0.0, 0.0),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(BitCrusherParam::default()),
+            phase: 0.0,
+            held: (0.0, 0.0),
+        }
+    }
+
+    fn quantize(&self, x: f32) -> f32 {
+        let bits = (MAX_BITS - (MAX_BITS - 1.0) * self.param.bits.get()).round();
+        let steps = (bits - 1.0).exp2();
+        (x * steps).round() / steps
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        if self.param.bypass.get() >= 0.5 {
+            return (left, right);
+        }
+
+        // Sample and hold at a fraction of the sample rate, tracked as a phase
+        // so non-integer ratios keep their average rate.
+        self.phase += MAX_REDUCTION.powf(-self.param.rate.get());
+        if self.phase >= 1.0 {
+            self.phase -= self.phase.floor();
+            self.held = (self.quantize(left), self.quantize(right));
+        }
+        self.held
+    }
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+use crate::parameters;
+use parameters::drive_param::{DriveParam, DriveStageParam};
+use std::sync::Arc;
+
+use super::vcf::clip;
+
+const MAX_DRIVE: f32 = 36.0; // dB of input gain at full amount
+const TUBE_BIAS: f32 = 0.3; // offsets the curve for even harmonics
+
+pub enum DriveShape {
+    TUBE,
+    DIODE, // the diode ladder's own tanh curve
+    HARD_CLIP,
+    FOLDBACK,
+}
+
+impl DriveShape {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 3.0).round() as i32 {
+            0 => Self::TUBE,
+            1 => Self::DIODE,
+            2 => Self::HARD_CLIP,
+            _ => Self::FOLDBACK,
+        }
+    }
+}
+
+fn shape(x: f32, shape: DriveShape) -> f32 {
+    match shape {
+        DriveShape::TUBE => clip(x + TUBE_BIAS) - clip(TUBE_BIAS),
+        DriveShape::DIODE => clip(x),
+        DriveShape::HARD_CLIP => x.max(-1.0).min(1.0),
+        // Reflects off ±1 instead of flattening, folding back and forth.
+        DriveShape::FOLDBACK => {
+            let t = 0.25 * (x + 1.0);
+            1.0 - 4.0 * (t - t.floor() - 0.5).abs()
+        }
+    }
+}
+
+// Waveshapers before and after the VCF. They are stateless and run inside the
+// oversampled loop, so the harmonics they add are decimated like the filter's.
+pub struct Drive {
+    param: Arc<DriveParam>,
+}
+
+impl Drive {
+    pub fn new(param: Arc<DriveParam>) -> Self {
+        Self { param: param }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(DriveParam::default()),
+        }
+    }
+
+    fn stage(&self, x: f32, param: &DriveStageParam) -> f32 {
+        let amount = param.amount.get();
+        if amount <= 0.0 {
+            return x;
+        }
+        let gain = (amount * MAX_DRIVE / 20.0 * std::f32::consts::LN_10).exp();
+        shape(x * gain, DriveShape::from_f32(param.shape.get()))
+    }
+
+    pub fn pre(&self, x: f32) -> f32 {
+        self.stage(x, &self.param.pre)
+    }
+
+    pub fn post(&self, x: f32) -> f32 {
+        self.stage(x, &self.param.post)
+    }
+}
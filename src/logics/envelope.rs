@@ -3,24 +3,72 @@ use crate::parameters;
 use parameters::envelope_param::EnvelopeParam;
 use std::sync::Arc;
 
+// Curvature 0.0 aims far past the stage end (almost linear), 1.0 aims just past
+// it (strongly exponential). The ratio is the overshoot relative to the stage span.
+const LINEAR_RATIO: f64 = 100.0;
+const EXPONENTIAL_RATIO: f64 = 0.001;
+
+#[derive(Clone, Copy, PartialEq)]
 enum State {
+    IDLE,
     ATTACK,
     DECAY,
+    SUSTAIN,
     RELEASE,
 }
 
-impl PartialEq for State {
-    fn eq(&self, other: &Self) -> bool {
-        self == other
+pub enum EnvelopeMode {
+    ADSR,
+    AD,        // decays to zero, gate length is ignored
+    AR,        // holds full level while the gate is on
+    LOOP_ADSR, // attack/decay cycle repeats while the gate is on
+    LOOP_AD,
+    ONE_SHOT, // full attack/decay/release on each trigger, gate length is ignored
+}
+
+impl EnvelopeMode {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 5.0).round() as i32 {
+            0 => Self::ADSR,
+            1 => Self::AD,
+            2 => Self::AR,
+            3 => Self::LOOP_ADSR,
+            4 => Self::LOOP_AD,
+            _ => Self::ONE_SHOT,
+        }
+    }
+
+    fn follows_gate(&self) -> bool {
+        match self {
+            Self::AD | Self::ONE_SHOT => false,
+            _ => true,
+        }
     }
+
+    fn decays_to_zero(&self) -> bool {
+        match self {
+            Self::AD | Self::LOOP_AD => true,
+            _ => false,
+        }
+    }
+}
+
+fn curve_ratio(curvature: f32) -> f64 {
+    LINEAR_RATIO * (EXPONENTIAL_RATIO / LINEAR_RATIO).powf(f64::from(curvature))
 }
 
 pub struct ADSR {
     envelope_param: Arc<EnvelopeParam>,
     sample_rate: f32,
     gate: bool,
-    state: i32,
-    output: f32,
+    state: State,
+    // Segments are run in f64: near-linear curves need a coefficient closer to
+    // one than f32 can resolve at long stage times.
+    output: f64,
+    coef: f64,
+    target: f64,
+    end: f64,
+    time_scale: f32,
 }
 
 impl ADSR {
@@ -29,8 +77,12 @@ impl ADSR {
             envelope_param: envelope_param,
             sample_rate: sample_rate,
             gate: false,
-            state: 2,
+            state: State::IDLE,
             output: 0.0,
+            coef: 0.0,
+            target: 0.0,
+            end: 0.0,
+            time_scale: 1.0,
         }
     }
 
@@ -39,59 +91,205 @@ impl ADSR {
             envelope_param: Arc::new(EnvelopeParam::default()),
             sample_rate: 44100.0,
             gate: false,
-            state: 2,
+            state: State::IDLE,
             output: 0.0,
+            coef: 0.0,
+            target: 0.0,
+            end: 0.0,
+            time_scale: 1.0,
         }
     }
 
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    // Stretches every stage time, e.g. for component tolerance.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale;
+    }
+
     pub fn set_param(&mut self, param: Arc<EnvelopeParam>) {
         self.envelope_param = param;
     }
 
     pub fn gate_on(&mut self) {
-        self.gate = true;
+        if !self.gate {
+            self.gate = true;
+            self.enter(State::ATTACK);
+        }
     }
 
     pub fn gate_off(&mut self) {
-        self.gate = false;
+        if self.gate {
+            self.gate = false;
+            if self.mode().follows_gate() && self.state != State::IDLE {
+                self.enter(State::RELEASE);
+            }
+        }
     }
 
     pub fn retrigger(&mut self) {
-        self.gate_off();
+        self.gate = false;
         self.output = 0.0;
-        self.state = 2;
+        self.state = State::IDLE;
     }
 
-    pub fn tick(&mut self) -> f32 {
-        if self.output < 0.98 && self.gate == true && self.state != 1 {
-            self.state = 0;
-        } else if self.output > 0.98 && self.gate == true {
-            self.state = 1;
-        } else if self.gate == false {
-            self.state = 2;
+    fn mode(&self) -> EnvelopeMode {
+        EnvelopeMode::from_f32(self.envelope_param.mode.get())
+    }
+
+    fn sustain_level(&self) -> f32 {
+        match self.mode() {
+            EnvelopeMode::AR => 1.0,
+            _ => self.envelope_param.sustain.get(),
         }
+    }
 
-        let mut alpha: f32 = 0.0;
-        let mut beta: f32 = 0.0;
+    // Sets up a one-pole segment that lands exactly on the stage end level after
+    // the stage time, aiming past it by the curvature's overshoot ratio.
+    fn enter(&mut self, state: State) {
+        let param = &self.envelope_param;
+        let decay_end = if self.mode().decays_to_zero() {
+            0.0
+        } else {
+            self.sustain_level()
+        };
+        let (time, end, curvature) = match state {
+            State::ATTACK => (param.attack.get(), 1.0, param.attack_curve.get()),
+            State::DECAY => (param.decay.get(), decay_end, param.decay_curve.get()),
+            State::RELEASE => (param.release.get(), 0.0, param.release_curve.get()),
+            State::SUSTAIN | State::IDLE => {
+                self.state = state;
+                return;
+            }
+        };
+        let end = f64::from(end);
 
-        match self.state {
-            0 => {
-                alpha = (-1.0 / (self.sample_rate * self.envelope_param.attack.get())).exp();
-                beta = 1.0;
+        self.state = state;
+        self.end = end;
+
+        // A stage that is already at its end level finishes on the next tick.
+        // Moving on straight away would recurse forever when a loop's attack
+        // and decay both end at full level.
+        let span = end - self.output;
+        if span.abs() < f64::from(f32::EPSILON) {
+            self.output = end;
+            self.target = end;
+            self.coef = 0.0;
+            return;
+        }
+
+        let ratio = curve_ratio(curvature);
+        let samples = f64::from(time * self.time_scale * self.sample_rate).max(1.0);
+        self.target = end + ratio * span;
+        self.coef = (ratio / (1.0 + ratio)).powf(1.0 / samples);
+    }
+
+    fn advance(&mut self) {
+        let mode = self.mode();
+        match (self.state, mode) {
+            (State::ATTACK, EnvelopeMode::AR) => self.enter(State::SUSTAIN),
+            (State::ATTACK, _) => self.enter(State::DECAY),
+            (State::DECAY, EnvelopeMode::ADSR) | (State::DECAY, EnvelopeMode::AR) => {
+                self.enter(State::SUSTAIN)
             }
-            1 => {
-                alpha = (-1.0 / (self.sample_rate * self.envelope_param.decay.get())).exp();
-                beta = self.envelope_param.sustain.get();
+            (State::DECAY, EnvelopeMode::AD) => self.enter(State::IDLE),
+            (State::DECAY, EnvelopeMode::LOOP_ADSR) | (State::DECAY, EnvelopeMode::LOOP_AD) => {
+                self.enter(State::ATTACK)
             }
-            2 => {
-                alpha = (-1.0 / (self.sample_rate * self.envelope_param.release.get())).exp();
-                beta = 0.0;
+            (State::DECAY, EnvelopeMode::ONE_SHOT) => self.enter(State::RELEASE),
+            (State::RELEASE, _) => self.enter(State::IDLE),
+            (State::SUSTAIN, _) | (State::IDLE, _) => (),
+        }
+    }
+
+    fn finished(&self) -> bool {
+        if self.target > self.end {
+            self.output >= self.end
+        } else {
+            self.output <= self.end
+        }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        match self.state {
+            State::IDLE => self.output = 0.0,
+            State::SUSTAIN => self.output = f64::from(self.sustain_level()),
+            State::ATTACK | State::DECAY | State::RELEASE => {
+                self.output = self.target + (self.output - self.target) * self.coef;
+                if self.finished() {
+                    self.output = self.end;
+                    self.advance();
+                }
             }
-            _ => (),
-        };
+        }
+
+        self.output as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOOP_ADSR: f32 = 0.6;
+    const LOOP_AD: f32 = 0.8;
 
-        self.output = self.output * alpha + (1.0 - alpha) * beta;
+    fn envelope(param: EnvelopeParam) -> ADSR {
+        let mut adsr = ADSR::new(Arc::new(param), 44100.0);
+        adsr.gate_on();
+        adsr
+    }
 
-        self.output
+    #[test]
+    fn loop_at_full_sustain_holds_full_level() {
+        let param = EnvelopeParam::default();
+        param.mode.set(LOOP_ADSR);
+        param.sustain.set(1.0);
+        let mut adsr = envelope(param);
+        for _ in 0..44100 {
+            adsr.tick();
+        }
+        for _ in 0..1000 {
+            assert_eq!(adsr.tick(), 1.0);
+        }
+    }
+
+    #[test]
+    fn loop_with_instant_attack_keeps_cycling() {
+        let param = EnvelopeParam::default();
+        param.mode.set(LOOP_AD);
+        param.attack.set(0.0);
+        let mut adsr = envelope(param);
+        let mut peaks = 0;
+        let mut previous = 0.0;
+        for _ in 0..44100 {
+            let output = adsr.tick();
+            assert!(output.is_finite() && output >= 0.0 && output <= 1.0);
+            if output == 1.0 && previous < 1.0 {
+                peaks += 1;
+            }
+            previous = output;
+        }
+        assert!(peaks > 1, "looped {} times", peaks);
+    }
+
+    #[test]
+    fn zero_length_loop_does_not_recurse() {
+        let param = EnvelopeParam::default();
+        param.mode.set(LOOP_ADSR);
+        param.attack.set(0.0);
+        param.decay.set(0.0);
+        param.sustain.set(1.0);
+        let mut adsr = envelope(param);
+        for _ in 0..1000 {
+            adsr.tick();
+        }
+        adsr.gate_off();
+        for _ in 0..44100 {
+            adsr.tick();
+        }
+        assert_eq!(adsr.tick(), 0.0);
     }
 }
@@ -0,0 +1,105 @@
+#[macro_use]
+use crate::parameters;
+use parameters::external_input_param::ExternalInputParam;
+use std::sync::Arc;
+
+const FOLLOWER_ATTACK: f32 = 0.001; // seconds
+const FOLLOWER_RELEASE: f32 = 0.05; // seconds
+const THRESHOLD_RANGE: f32 = 60.0; // dB below full scale
+const GATE_HYSTERESIS: f32 = 0.5; // the gate closes 6 dB below where it opened
+
+pub enum TriggerMode {
+    OFF,
+    GATE,     // the input's gate triggers the envelopes
+    FOLLOWER, // the input's envelope replaces the VCA and MOD envelopes
+}
+
+impl TriggerMode {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::OFF,
+            1 => Self::GATE,
+            _ => Self::FOLLOWER,
+        }
+    }
+}
+
+// The audio inputs as a mixer channel, plus an envelope follower and gate
+// detector on the unscaled input so the trigger doesn't depend on the level.
+pub struct ExternalInput {
+    param: Arc<ExternalInputParam>,
+    sample_rate: f32,
+    envelope: f32,
+    gate: bool,
+    edge: Option<bool>,
+}
+
+impl ExternalInput {
+    pub fn new(param: Arc<ExternalInputParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            sample_rate: sample_rate,
+            envelope: 0.0,
+            gate: false,
+            edge: None,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(ExternalInputParam::default()),
+            sample_rate: 44100.0,
+            envelope: 0.0,
+            gate: false,
+            edge: None,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn mode(&self) -> TriggerMode {
+        TriggerMode::from_f32(self.param.trigger.get())
+    }
+
+    fn threshold(&self) -> f32 {
+        let db = -THRESHOLD_RANGE * (1.0 - self.param.threshold.get());
+        (db / 20.0 * std::f32::consts::LN_10).exp()
+    }
+
+    // Returns the mono input scaled by the channel level.
+    pub fn tick(&mut self, left: f32, right: f32) -> f32 {
+        let input = (left + right) * 0.5;
+
+        let rectified = input.abs();
+        let time = if rectified > self.envelope {
+            FOLLOWER_ATTACK
+        } else {
+            FOLLOWER_RELEASE
+        };
+        let coef = (-1.0 / (time * self.sample_rate)).exp();
+        self.envelope = rectified + (self.envelope - rectified) * coef;
+
+        let threshold = self.threshold();
+        self.edge = None;
+        if !self.gate && self.envelope >= threshold {
+            self.gate = true;
+            self.edge = Some(true);
+        } else if self.gate && self.envelope < threshold * GATE_HYSTERESIS {
+            self.gate = false;
+            self.edge = Some(false);
+        }
+
+        input * self.param.level.get()
+    }
+
+    pub fn envelope(&self) -> f32 {
+        self.envelope.min(1.0)
+    }
+
+    // Some(true) on the sample the gate opens, Some(false) when it closes.
+    pub fn gate_edge(&self) -> Option<bool> {
+        self.edge
+    }
+}
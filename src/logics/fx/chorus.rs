@@ -0,0 +1,72 @@
+#[macro_use]
+use crate::parameters;
+use parameters::fx_param::FXParam;
+use std::sync::Arc;
+
+use super::{mix, DelayLine, OnePole};
+
+const BASE_DELAY: f32 = 0.007; // seconds
+const MAX_DEPTH: f32 = 0.005; // seconds either way
+const MIN_RATE: f32 = 0.1; // Hz
+const RATE_RANGE: f32 = 100.0; // max rate / min rate
+const BBD_CUTOFF: f32 = 8000.0; // Hz, the bucket brigade's clock filtering
+
+// BBD-style chorus: the mono sum goes through one bucket brigade read by two
+// taps swept in antiphase by a triangle LFO, which makes the output stereo.
+pub struct Chorus {
+    param: Arc<FXParam>,
+    sample_rate: f32,
+    line: DelayLine,
+    phase: f32,
+    filters: [OnePole; 2],
+}
+
+impl Chorus {
+    pub fn new(param: Arc<FXParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            sample_rate: sample_rate,
+            line: DelayLine::new(Self::length(sample_rate)),
+            phase: 0.0,
+            filters: [OnePole::new(), OnePole::new()],
+        }
+    }
+
+    fn length(sample_rate: f32) -> usize {
+        ((BASE_DELAY + MAX_DEPTH) * sample_rate) as usize + 2
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.line = DelayLine::new(Self::length(sample_rate));
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let param = &self.param.chorus;
+        if param.bypass.get() >= 0.5 {
+            return (left, right);
+        }
+
+        // A BBD's input stage saturates gently.
+        self.line.push(((left + right) * 0.5).tanh());
+
+        let rate = MIN_RATE * RATE_RANGE.powf(param.rate.get());
+        self.phase += rate / self.sample_rate;
+        self.phase -= self.phase.floor();
+        let triangle = 4.0 * (self.phase - 0.5).abs() - 1.0;
+
+        let sweep = param.depth.get() * MAX_DEPTH * triangle;
+        let coefficient = OnePole::coefficient(BBD_CUTOFF, self.sample_rate);
+        let wet_left = self.filters[0].lowpass(
+            self.line.read((BASE_DELAY + sweep) * self.sample_rate),
+            coefficient,
+        );
+        let wet_right = self.filters[1].lowpass(
+            self.line.read((BASE_DELAY - sweep) * self.sample_rate),
+            coefficient,
+        );
+
+        let amount = param.mix.get();
+        (mix(left, wet_left, amount), mix(right, wet_right, amount))
+    }
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+use crate::parameters;
+use parameters::fx_param::FXParam;
+use std::sync::Arc;
+
+use super::{mix, DelayLine, OnePole};
+
+const MAX_TIME: f32 = 4.0; // seconds
+const DEFAULT_TEMPO: f32 = 120.0; // bpm, until the host reports one
+const SMOOTHING_TIME: f32 = 0.05; // seconds, glides tempo and division changes
+const MAX_FEEDBACK: f32 = 0.95;
+const FEEDBACK_HIGHPASS: f32 = 80.0; // Hz, keeps the lows from building up
+const MIN_TONE: f32 = 500.0; // Hz
+const TONE_RANGE: f32 = 40.0; // max tone cutoff / min tone cutoff
+
+// Note divisions in beats: 1/16, 1/8T, 1/8, 1/4T, 1/8D, 1/4, 1/2T, 1/4D, 1/2.
+const DIVISIONS: [f32; 9] = [
+    0.25,
+    1.0 / 3.0,
+    0.5,
+    2.0 / 3.0,
+    0.75,
+    1.0,
+    4.0 / 3.0,
+    1.5,
+    2.0,
+];
+
+pub fn division_from_f32(value: f32) -> f32 {
+    DIVISIONS[(value * (DIVISIONS.len() - 1) as f32).round() as usize]
+}
+
+// Tempo-synced stereo delay with a lowpass (tone) and highpass in the
+// feedback path, so each repeat gets darker and thinner.
+pub struct Delay {
+    param: Arc<FXParam>,
+    sample_rate: f32,
+    tempo: f32,
+    time: f32, // smoothed delay in samples
+    lines: [DelayLine; 2],
+    lowpass: [OnePole; 2],
+    highpass: [OnePole; 2],
+}
+
+impl Delay {
+    pub fn new(param: Arc<FXParam>, sample_rate: f32) -> Self {
+        let mut delay = Self {
+            param: param,
+            sample_rate: sample_rate,
+            tempo: DEFAULT_TEMPO,
+            time: 0.0,
+            lines: [
+                DelayLine::new(Self::length(sample_rate)),
+                DelayLine::new(Self::length(sample_rate)),
+            ],
+            lowpass: [OnePole::new(), OnePole::new()],
+            highpass: [OnePole::new(), OnePole::new()],
+        };
+        delay.time = delay.target_time();
+        delay
+    }
+
+    fn length(sample_rate: f32) -> usize {
+        (MAX_TIME * sample_rate) as usize + 2
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.lines = [
+            DelayLine::new(Self::length(sample_rate)),
+            DelayLine::new(Self::length(sample_rate)),
+        ];
+        self.time = self.target_time();
+    }
+
+    pub fn set_tempo(&mut self, bpm: f32) {
+        if bpm > 0.0 {
+            self.tempo = bpm;
+        }
+    }
+
+    fn target_time(&self) -> f32 {
+        let beats = division_from_f32(self.param.delay.time.get());
+        (beats * 60.0 / self.tempo).min(MAX_TIME) * self.sample_rate
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let param = &self.param.delay;
+        if param.bypass.get() >= 0.5 {
+            return (left, right);
+        }
+
+        let smoothing = OnePole::coefficient(1.0 / SMOOTHING_TIME, self.sample_rate);
+        self.time += (self.target_time() - self.time) * smoothing;
+
+        let feedback = param.feedback.get() * MAX_FEEDBACK;
+        let tone = MIN_TONE * TONE_RANGE.powf(param.tone.get());
+        let lowpass = OnePole::coefficient(tone, self.sample_rate);
+        let highpass = OnePole::coefficient(FEEDBACK_HIGHPASS, self.sample_rate);
+        let amount = param.mix.get();
+
+        let mut out = [left, right];
+        for (i, sample) in out.iter_mut().enumerate() {
+            // Read before this sample is pushed, so the newest sample is one old.
+            let delayed = self.lines[i].read(self.time - 1.0);
+            let filtered = self.lowpass[i].lowpass(delayed, lowpass);
+            let filtered = self.highpass[i].highpass(filtered, highpass);
+            self.lines[i].push(*sample + filtered * feedback);
+            *sample = mix(*sample, delayed, amount);
+        }
+        (out[0], out[1])
+    }
+}
@@ -0,0 +1,114 @@
+pub mod chorus;
+pub mod delay;
+pub mod reverb;
+
+#[macro_use]
+use crate::parameters;
+use parameters::fx_param::FXParam;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use chorus::Chorus;
+use delay::Delay;
+use reverb::Reverb;
+
+// Circular buffer shared by the effects. Delays are in samples; fractional
+// delays are read with linear interpolation.
+struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl DelayLine {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(2)],
+            position: 0,
+        }
+    }
+
+    fn push(&mut self, x: f32) {
+        self.position = (self.position + 1) % self.buffer.len();
+        self.buffer[self.position] = x;
+    }
+
+    fn tap(&self, delay: usize) -> f32 {
+        let len = self.buffer.len();
+        self.buffer[(self.position + len - delay.min(len - 1)) % len]
+    }
+
+    fn read(&self, delay: f32) -> f32 {
+        let delay = delay.max(0.0).min((self.buffer.len() - 2) as f32);
+        let whole = delay.floor();
+        let fraction = delay - whole;
+        let a = self.tap(whole as usize);
+        let b = self.tap(whole as usize + 1);
+        a + (b - a) * fraction
+    }
+}
+
+// Plain one-pole lowpass; highpass is the input minus the lowpass.
+struct OnePole {
+    z1: f32,
+}
+
+impl OnePole {
+    fn new() -> Self {
+        Self { z1: 0.0 }
+    }
+
+    fn coefficient(cutoff: f32, sample_rate: f32) -> f32 {
+        1.0 - (-2.0 * PI * cutoff / sample_rate).exp()
+    }
+
+    fn lowpass(&mut self, x: f32, coefficient: f32) -> f32 {
+        self.z1 += (x - self.z1) * coefficient;
+        self.z1
+    }
+
+    fn highpass(&mut self, x: f32, coefficient: f32) -> f32 {
+        x - self.lowpass(x, coefficient)
+    }
+}
+
+fn mix(dry: f32, wet: f32, amount: f32) -> f32 {
+    dry + (wet - dry) * amount
+}
+
+// Post-VCA chain: chorus into delay into reverb. Bypassed effects are skipped
+// entirely.
+pub struct FX {
+    chorus: Chorus,
+    delay: Delay,
+    reverb: Reverb,
+}
+
+impl FX {
+    pub fn new(param: Arc<FXParam>, sample_rate: f32) -> Self {
+        Self {
+            chorus: Chorus::new(param.clone(), sample_rate),
+            delay: Delay::new(param.clone(), sample_rate),
+            reverb: Reverb::new(param.clone(), sample_rate),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self::new(Arc::new(FXParam::default()), 44100.0)
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.chorus.set_sample_rate(sample_rate);
+        self.delay.set_sample_rate(sample_rate);
+        self.reverb.set_sample_rate(sample_rate);
+    }
+
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.delay.set_tempo(bpm);
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let (left, right) = self.chorus.process(left, right);
+        let (left, right) = self.delay.process(left, right);
+        self.reverb.process(left, right)
+    }
+}
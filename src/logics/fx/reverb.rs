@@ -0,0 +1,134 @@
+#[macro_use]
+use crate::parameters;
+use parameters::fx_param::FXParam;
+use std::sync::Arc;
+
+use super::mix;
+
+// Schroeder/Moorer reverb after Freeverb, cut down to four combs and two
+// allpasses per channel. Tunings are in samples at 44.1kHz.
+const COMB_TUNINGS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_TUNINGS: [usize; 2] = [556, 441];
+const STEREO_SPREAD: usize = 23;
+const TUNING_RATE: f32 = 44100.0;
+
+const INPUT_GAIN: f32 = 0.03;
+const MIN_ROOM: f32 = 0.7; // comb feedback at the smallest size
+const ROOM_RANGE: f32 = 0.28;
+const MAX_DAMPING: f32 = 0.4;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+// Feedback comb with a lowpass in the loop.
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filtered: f32,
+}
+
+impl Comb {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            index: 0,
+            filtered: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f32, feedback: f32, damping: f32) -> f32 {
+        let out = self.buffer[self.index];
+        self.filtered = out + (self.filtered - out) * damping;
+        self.buffer[self.index] = x + self.filtered * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        out
+    }
+}
+
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Allpass {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            index: 0,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = x + delayed * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        delayed - x
+    }
+}
+
+struct Channel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Channel {
+    fn new(sample_rate: f32, spread: usize) -> Self {
+        let scale = |tuning: usize| ((tuning + spread) as f32 * sample_rate / TUNING_RATE) as usize;
+        Self {
+            combs: COMB_TUNINGS.iter().map(|&t| Comb::new(scale(t))).collect(),
+            allpasses: ALLPASS_TUNINGS
+                .iter()
+                .map(|&t| Allpass::new(scale(t)))
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, x: f32, feedback: f32, damping: f32) -> f32 {
+        let mut out = 0.0;
+        for comb in self.combs.iter_mut() {
+            out += comb.process(x, feedback, damping);
+        }
+        for allpass in self.allpasses.iter_mut() {
+            out = allpass.process(out);
+        }
+        out
+    }
+}
+
+pub struct Reverb {
+    param: Arc<FXParam>,
+    channels: [Channel; 2],
+}
+
+impl Reverb {
+    pub fn new(param: Arc<FXParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            channels: [
+                Channel::new(sample_rate, 0),
+                Channel::new(sample_rate, STEREO_SPREAD),
+            ],
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.channels = [
+            Channel::new(sample_rate, 0),
+            Channel::new(sample_rate, STEREO_SPREAD),
+        ];
+    }
+
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let param = &self.param.reverb;
+        if param.bypass.get() >= 0.5 {
+            return (left, right);
+        }
+
+        let input = (left + right) * 0.5 * INPUT_GAIN;
+        let feedback = MIN_ROOM + ROOM_RANGE * param.size.get();
+        let damping = MAX_DAMPING * param.damping.get();
+        let wet_left = self.channels[0].process(input, feedback, damping);
+        let wet_right = self.channels[1].process(input, feedback, damping);
+
+        let amount = param.mix.get();
+        (mix(left, wet_left, amount), mix(right, wet_right, amount))
+    }
+}
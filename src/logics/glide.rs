@@ -0,0 +1,41 @@
+pub struct Glide {
+    sample_rate: f32,
+    note: f32,
+    target: f32,
+    coef: f32,
+}
+
+impl Glide {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate: sample_rate,
+            note: 69.0,
+            target: 69.0,
+            coef: 0.0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn jump(&mut self, note: f32) {
+        self.note = note;
+        self.target = note;
+    }
+
+    // Glides exponentially in the pitch domain, `time` being the time constant.
+    pub fn slide(&mut self, note: f32, time: f32) {
+        self.target = note;
+        self.coef = if time > 0.0 {
+            (-1.0 / (time * self.sample_rate)).exp()
+        } else {
+            0.0
+        };
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        self.note = self.target + (self.note - self.target) * self.coef;
+        self.note
+    }
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+use crate::parameters;
+use parameters::lfo_param::LFOParam;
+use rand::distributions::Standard;
+use rand::prelude::*;
+use std::sync::Arc;
+
+const MIN_RATE: f32 = 0.1; // Hz
+const RATE_RANGE: f32 = 300.0; // max rate / min rate
+
+pub enum LFOWaveform {
+    TRIANGLE,
+    SQUARE,
+    RANDOM, // sample & hold, a new value every cycle
+    NOISE,
+}
+
+impl LFOWaveform {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 3.0).round() as i32 {
+            0 => Self::TRIANGLE,
+            1 => Self::SQUARE,
+            2 => Self::RANDOM,
+            _ => Self::NOISE,
+        }
+    }
+}
+
+pub struct LFO {
+    param: Arc<LFOParam>,
+    sample_rate: f32,
+    phase: f32,
+    held: f32,
+    rng: StdRng,
+}
+
+impl LFO {
+    pub fn new(param: Arc<LFOParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            sample_rate: sample_rate,
+            phase: 0.0,
+            held: 0.0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(LFOParam::default()),
+            sample_rate: 44100.0,
+            phase: 0.0,
+            held: 0.0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn random(&mut self) -> f32 {
+        self.rng.sample::<f32, Standard>(Standard) * 2.0 - 1.0
+    }
+
+    // Bipolar output in [-1, 1].
+    pub fn tick(&mut self) -> f32 {
+        let freq = MIN_RATE * RATE_RANGE.powf(self.param.rate.get());
+        self.phase += freq / self.sample_rate;
+        if self.phase >= 1.0 {
+            self.phase -= self.phase.floor();
+            self.held = self.random();
+        }
+
+        match LFOWaveform::from_f32(self.param.waveform.get()) {
+            LFOWaveform::TRIANGLE => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            LFOWaveform::SQUARE => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LFOWaveform::RANDOM => self.held,
+            LFOWaveform::NOISE => self.random(),
+        }
+    }
+}
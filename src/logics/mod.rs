@@ -1,3 +1,16 @@
+pub mod amplifier;
+pub mod analog;
+pub mod bit_crusher;
+pub mod drive;
 pub mod envelope;
+pub mod external_input;
+pub mod fx;
+pub mod glide;
+pub mod lfo;
+pub mod mod_matrix;
+pub mod output;
+pub mod oversampler;
+pub mod tuning;
+pub mod unison;
 pub mod vcf;
 pub mod vco;
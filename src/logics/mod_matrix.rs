@@ -0,0 +1,142 @@
+#[macro_use]
+use crate::parameters;
+use parameters::mod_matrix_param::ModMatrixParam;
+use std::sync::Arc;
+
+// Full-scale modulation depth per destination.
+const PITCH_RANGE: f32 = 24.0; // semitones
+const CUTOFF_RANGE: f32 = 8.0; // octaves
+
+pub enum ModSource {
+    NONE,
+    VCA_ENVELOPE,
+    MOD_ENVELOPE,
+    LFO,
+    VELOCITY,
+    MOD_WHEEL,
+    AFTERTOUCH,
+    KEYBOARD,
+}
+
+impl ModSource {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 7.0).round() as i32 {
+            0 => Self::NONE,
+            1 => Self::VCA_ENVELOPE,
+            2 => Self::MOD_ENVELOPE,
+            3 => Self::LFO,
+            4 => Self::VELOCITY,
+            5 => Self::MOD_WHEEL,
+            6 => Self::AFTERTOUCH,
+            _ => Self::KEYBOARD,
+        }
+    }
+}
+
+pub enum ModDestination {
+    NONE,
+    PITCH,
+    PULSE_WIDTH,
+    CUTOFF,
+    RESONANCE,
+    SAW_LEVEL,
+    TRI_LEVEL,
+    SQU_LEVEL,
+    SUB_LEVEL,
+    NOISE_LEVEL,
+    VCA,
+}
+
+impl ModDestination {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 10.0).round() as i32 {
+            0 => Self::NONE,
+            1 => Self::PITCH,
+            2 => Self::PULSE_WIDTH,
+            3 => Self::CUTOFF,
+            4 => Self::RESONANCE,
+            5 => Self::SAW_LEVEL,
+            6 => Self::TRI_LEVEL,
+            7 => Self::SQU_LEVEL,
+            8 => Self::SUB_LEVEL,
+            9 => Self::NOISE_LEVEL,
+            _ => Self::VCA,
+        }
+    }
+}
+
+// Current value of every modulation source. Envelopes, velocity, wheel and
+// aftertouch are unipolar, the LFO and keyboard are bipolar.
+#[derive(Default)]
+pub struct ModSources {
+    pub vca_envelope: f32,
+    pub mod_envelope: f32,
+    pub lfo: f32,
+    pub velocity: f32,
+    pub mod_wheel: f32,
+    pub aftertouch: f32,
+    pub keyboard: f32,
+}
+
+// Summed modulation per destination, already scaled to destination units:
+// semitones for pitch, octaves for cutoff, and plain offsets for the rest.
+#[derive(Default)]
+pub struct ModDestinations {
+    pub pitch: f32,
+    pub pulse_width: f32,
+    pub cutoff: f32,
+    pub resonance: f32,
+    pub saw_level: f32,
+    pub tri_level: f32,
+    pub squ_level: f32,
+    pub sub_level: f32,
+    pub noise_level: f32,
+    pub vca: f32,
+}
+
+pub struct ModMatrix {
+    param: Arc<ModMatrixParam>,
+}
+
+impl ModMatrix {
+    pub fn new(param: Arc<ModMatrixParam>) -> Self {
+        Self { param: param }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(ModMatrixParam::default()),
+        }
+    }
+
+    pub fn process(&self, sources: &ModSources) -> ModDestinations {
+        let mut destinations = ModDestinations::default();
+        for slot in self.param.slots.iter() {
+            let value = match ModSource::from_f32(slot.source.get()) {
+                ModSource::NONE => continue,
+                ModSource::VCA_ENVELOPE => sources.vca_envelope,
+                ModSource::MOD_ENVELOPE => sources.mod_envelope,
+                ModSource::LFO => sources.lfo,
+                ModSource::VELOCITY => sources.velocity,
+                ModSource::MOD_WHEEL => sources.mod_wheel,
+                ModSource::AFTERTOUCH => sources.aftertouch,
+                ModSource::KEYBOARD => sources.keyboard,
+            } * (slot.amount.get() * 2.0 - 1.0);
+
+            match ModDestination::from_f32(slot.destination.get()) {
+                ModDestination::NONE => (),
+                ModDestination::PITCH => destinations.pitch += value * PITCH_RANGE,
+                ModDestination::PULSE_WIDTH => destinations.pulse_width += value,
+                ModDestination::CUTOFF => destinations.cutoff += value * CUTOFF_RANGE,
+                ModDestination::RESONANCE => destinations.resonance += value,
+                ModDestination::SAW_LEVEL => destinations.saw_level += value,
+                ModDestination::TRI_LEVEL => destinations.tri_level += value,
+                ModDestination::SQU_LEVEL => destinations.squ_level += value,
+                ModDestination::SUB_LEVEL => destinations.sub_level += value,
+                ModDestination::NOISE_LEVEL => destinations.noise_level += value,
+                ModDestination::VCA => destinations.vca += value,
+            }
+        }
+        destinations
+    }
+}
@@ -0,0 +1,79 @@
+#[macro_use]
+use crate::parameters;
+use parameters::output_param::OutputParam;
+use std::f32::consts::{FRAC_PI_2, SQRT_2};
+use std::sync::Arc;
+
+// Volume is linear in dB over VOLUME_RANGE up to MAX_VOLUME; the bottom of the
+// range mutes.
+const MAX_VOLUME: f32 = 6.0; // dB
+const VOLUME_RANGE: f32 = 60.0; // dB
+
+// Below the threshold the clipper is transparent; above it the signal bends
+// smoothly towards the ceiling and never exceeds it.
+const CLIP_THRESHOLD: f32 = 0.8;
+const CLIP_CEILING: f32 = 1.0;
+
+const MAX_WIDTH: f32 = 2.0; // side gain at full width
+
+pub struct Output {
+    param: Arc<OutputParam>,
+}
+
+impl Output {
+    pub fn new(param: Arc<OutputParam>) -> Self {
+        Self { param: param }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            param: Arc::new(OutputParam::default()),
+        }
+    }
+
+    fn gain(&self) -> f32 {
+        let volume = self.param.volume.get();
+        if volume <= 0.0 {
+            0.0
+        } else {
+            let db = MAX_VOLUME - VOLUME_RANGE * (1.0 - volume);
+            (db / 20.0 * std::f32::consts::LN_10).exp()
+        }
+    }
+
+    fn soft_clip(&self, x: f32) -> f32 {
+        let magnitude = x.abs();
+        if magnitude <= CLIP_THRESHOLD {
+            x
+        } else {
+            let knee = CLIP_CEILING - CLIP_THRESHOLD;
+            let bent = CLIP_THRESHOLD + knee * ((magnitude - CLIP_THRESHOLD) / knee).tanh();
+            bent.copysign(x)
+        }
+    }
+
+    // Stereo stage after the VCA: width scales the side signal, then an
+    // equal-power pan normalised to unity at the centre.
+    pub fn stereo(&self, left: f32, right: f32) -> (f32, f32) {
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5 * self.param.width.get() * MAX_WIDTH;
+        let angle = self.param.pan.get() * FRAC_PI_2;
+        (
+            (mid + side) * angle.cos() * SQRT_2,
+            (mid - side) * angle.sin() * SQRT_2,
+        )
+    }
+
+    // Final stage after any effects. Mono mode folds both channels together
+    // so the patch can be checked for mono compatibility.
+    pub fn process(&self, left: f32, right: f32) -> (f32, f32) {
+        let (left, right) = if self.param.mono.get() >= 0.5 {
+            let mid = (left + right) * 0.5;
+            (mid, mid)
+        } else {
+            (left, right)
+        };
+        let gain = self.gain();
+        (self.soft_clip(left * gain), self.soft_clip(right * gain))
+    }
+}
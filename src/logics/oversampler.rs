@@ -0,0 +1,342 @@
+use std::f32::consts::PI;
+
+const MAX_FACTOR: usize = 8;
+const MAX_STAGES: usize = 3; // log2(MAX_FACTOR)
+const HALFBAND_TAPS: usize = 47;
+const EVEN_TAPS: usize = (HALFBAND_TAPS + 1) / 2;
+const ODD_DELAY: usize = (HALFBAND_TAPS - 1) / 4 + 1;
+
+pub fn factor_from_f32(value: f32) -> usize {
+    match (value * 3.0).round() as i32 {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    }
+}
+
+// Even taps of a Blackman-windowed halfband lowpass, normalised so they sum to
+// 0.5. Every other tap of a halfband FIR is zero except the centre one, which
+// is 0.5, so the odd phase reduces to a pure delay.
+fn halfband_coefficients() -> [f32; EVEN_TAPS] {
+    let center = (HALFBAND_TAPS - 1) as f32 / 2.0;
+    let mut coefficients = [0.0; EVEN_TAPS];
+    for (i, c) in coefficients.iter_mut().enumerate() {
+        let n = (2 * i) as f32;
+        let x = 0.5 * (n - center);
+        let sinc = (PI * x).sin() / (PI * x);
+        let window = 0.42 - 0.5 * (2.0 * PI * n / (HALFBAND_TAPS - 1) as f32).cos()
+            + 0.08 * (4.0 * PI * n / (HALFBAND_TAPS - 1) as f32).cos();
+        *c = 0.5 * sinc * window;
+    }
+    let sum: f32 = coefficients.iter().sum();
+    for c in coefficients.iter_mut() {
+        *c *= 0.5 / sum;
+    }
+    coefficients
+}
+
+fn convolve(coefficients: &[f32; EVEN_TAPS], history: &[f32; EVEN_TAPS]) -> f32 {
+    coefficients
+        .iter()
+        .zip(history.iter())
+        .map(|(c, x)| c * x)
+        .sum()
+}
+
+// Halfband lowpass decimating by two in polyphase form; only the even phase
+// needs a convolution.
+struct HalfbandDecimator {
+    coefficients: [f32; EVEN_TAPS],
+    even: [f32; EVEN_TAPS],
+    odd: [f32; ODD_DELAY],
+}
+
+impl HalfbandDecimator {
+    fn new() -> Self {
+        Self {
+            coefficients: halfband_coefficients(),
+            even: [0.0; EVEN_TAPS],
+            odd: [0.0; ODD_DELAY],
+        }
+    }
+
+    // Fills the history with a constant, which the unity DC gain passes
+    // straight through.
+    fn reset(&mut self, value: f32) {
+        self.even = [value; EVEN_TAPS];
+        self.odd = [value; ODD_DELAY];
+    }
+
+    // Takes two consecutive input samples and returns one output sample.
+    fn process(&mut self, x0: f32, x1: f32) -> f32 {
+        self.even.copy_within(0..EVEN_TAPS - 1, 1);
+        self.even[0] = x1;
+        self.odd.copy_within(0..ODD_DELAY - 1, 1);
+        self.odd[0] = x0;
+
+        convolve(&self.coefficients, &self.even) + 0.5 * self.odd[ODD_DELAY - 1]
+    }
+}
+
+// The same halfband interpolating by two: the zero-stuffed input only meets
+// the even taps on one output phase and the centre tap on the other.
+struct HalfbandInterpolator {
+    coefficients: [f32; EVEN_TAPS],
+    history: [f32; EVEN_TAPS],
+}
+
+impl HalfbandInterpolator {
+    fn new() -> Self {
+        Self {
+            coefficients: halfband_coefficients(),
+            history: [0.0; EVEN_TAPS],
+        }
+    }
+
+    fn reset(&mut self, value: f32) {
+        self.history = [value; EVEN_TAPS];
+    }
+
+    // Takes one input sample and returns two consecutive output samples.
+    fn process(&mut self, x: f32) -> (f32, f32) {
+        self.history.copy_within(0..EVEN_TAPS - 1, 1);
+        self.history[0] = x;
+        // Zero-stuffing halves the level, so both phases are doubled.
+        (
+            2.0 * convolve(&self.coefficients, &self.history),
+            self.history[ODD_DELAY - 1],
+        )
+    }
+}
+
+pub struct Oversampler {
+    factor: usize,
+    stages: [HalfbandDecimator; MAX_STAGES],
+    input_stages: [HalfbandInterpolator; MAX_STAGES],
+    last: f32,
+    last_input: f32,
+}
+
+impl Oversampler {
+    pub fn new() -> Self {
+        Self {
+            factor: 1,
+            stages: [
+                HalfbandDecimator::new(),
+                HalfbandDecimator::new(),
+                HalfbandDecimator::new(),
+            ],
+            input_stages: [
+                HalfbandInterpolator::new(),
+                HalfbandInterpolator::new(),
+                HalfbandInterpolator::new(),
+            ],
+            last: 0.0,
+            last_input: 0.0,
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    pub fn set_factor(&mut self, factor: usize) {
+        self.factor = factor.min(MAX_FACTOR).max(1);
+        // History recorded at the old rate would be replayed at the wrong
+        // speed, so it is replaced by the last output held as DC. The signal
+        // then carries on from where it was instead of dropping to zero.
+        for stage in self.stages.iter_mut() {
+            stage.reset(self.last);
+        }
+        for stage in self.input_stages.iter_mut() {
+            stage.reset(self.last_input);
+        }
+    }
+
+    // Interpolates a base-rate `input` up to the oversampled rate, so it
+    // reaches the nonlinear stages without zero-order-hold images.
+    fn upsample(&mut self, input: f32) -> [f32; MAX_FACTOR] {
+        self.last_input = input;
+        let mut buffer = [0.0; MAX_FACTOR];
+        buffer[0] = input;
+        let mut len = 1;
+        let mut stage = 0;
+        while len < self.factor {
+            let previous = buffer;
+            for (i, x) in previous.iter().take(len).enumerate() {
+                let (y0, y1) = self.input_stages[stage].process(*x);
+                buffer[2 * i] = y0;
+                buffer[2 * i + 1] = y1;
+            }
+            len *= 2;
+            stage += 1;
+        }
+        buffer
+    }
+
+    // Runs `tick` once per oversampled sample with the upsampled `input`, and
+    // decimates the result back down to the base rate.
+    pub fn process<F: FnMut(f32) -> f32>(&mut self, input: f32, mut tick: F) -> f32 {
+        let mut buffer = self.upsample(input);
+        for sample in buffer.iter_mut().take(self.factor) {
+            *sample = tick(*sample);
+        }
+
+        let mut len = self.factor;
+        let mut stage = 0;
+        while len > 1 {
+            for i in 0..len / 2 {
+                buffer[i] = self.stages[stage].process(buffer[2 * i], buffer[2 * i + 1]);
+            }
+            len /= 2;
+            stage += 1;
+        }
+        self.last = buffer[0];
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const SETTLE: usize = 200;
+    const MEASURE: usize = 2000;
+    const STOPBAND_GAIN: f32 = 0.0001; // -80dB // -60dB
+
+    // Peak output level for a unit sine at `freq`, generated at the
+    // oversampled rate.
+    fn peak(factor: usize, freq: f32) -> f32 {
+        let mut oversampler = Oversampler::new();
+        oversampler.set_factor(factor);
+        let increment = freq / (SAMPLE_RATE * factor as f32);
+        let mut phase = 0.0f32;
+        let mut peak = 0.0f32;
+        for i in 0..SETTLE + MEASURE {
+            let y = oversampler.process(0.0, |_| {
+                phase = (phase + increment).fract();
+                (2.0 * PI * phase).sin()
+            });
+            if i >= SETTLE {
+                peak = peak.max(y.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn passes_the_audio_band_at_unity_gain() {
+        for &factor in [1, 2, 4, 8].iter() {
+            for &freq in [100.0, 1000.0, 10000.0].iter() {
+                let gain = peak(factor, freq);
+                assert!(
+                    (gain - 1.0).abs() < 0.01,
+                    "{}Hz at {}x comes through at {}",
+                    freq,
+                    factor,
+                    gain
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn attenuates_tones_above_the_base_nyquist() {
+        for &factor in [2, 4, 8].iter() {
+            for &ratio in [0.65, 0.75, 0.9].iter() {
+                let freq = SAMPLE_RATE * ratio;
+                let gain = peak(factor, freq);
+                assert!(
+                    gain < STOPBAND_GAIN,
+                    "{}Hz at {}x aliases back at {}",
+                    freq,
+                    factor,
+                    gain
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn switching_factor_mid_stream_leaves_no_stale_state() {
+        let mut oversampler = Oversampler::new();
+        let freq = 20.0;
+        let mut time = 0.0f64;
+        let mut previous = 0.0;
+        let mut largest_step = 0.0f32;
+        for (i, &factor) in [1, 8, 2, 4, 1, 4, 8, 2].iter().cycle().take(24).enumerate() {
+            oversampler.set_factor(factor);
+            let step = 1.0 / (f64::from(SAMPLE_RATE) * factor as f64);
+            for j in 0..500 {
+                let y = oversampler.process(0.0, |_| {
+                    time += step;
+                    (2.0 * std::f64::consts::PI * freq * time).sin() as f32
+                });
+                assert!(y.is_finite());
+                if i > 0 || j > 0 {
+                    largest_step = largest_step.max((y - previous).abs());
+                }
+                previous = y;
+            }
+        }
+        // A 20Hz unit sine moves by at most 0.003 per base-rate sample. The
+        // latency changes by up to 20 samples between factors, which leaves a
+        // step of about 0.06; dropping stale history to zero would jump by up
+        // to the full amplitude.
+        assert!(largest_step < 0.1, "output jumps by {}", largest_step);
+    }
+
+    // Level of `freq` in `samples` taken at `sample_rate`.
+    fn level(samples: &[f32], freq: f32, sample_rate: f32) -> f32 {
+        let (mut re, mut im) = (0.0f64, 0.0f64);
+        for (n, x) in samples.iter().enumerate() {
+            let angle =
+                2.0 * std::f64::consts::PI * f64::from(freq) * n as f64 / f64::from(sample_rate);
+            re += f64::from(*x) * angle.cos();
+            im += f64::from(*x) * angle.sin();
+        }
+        (2.0 * (re * re + im * im).sqrt() / samples.len() as f64) as f32
+    }
+
+    #[test]
+    fn upsamples_the_input_without_images() {
+        let freq = 10000.0;
+        for &factor in [2, 4, 8].iter() {
+            let mut oversampler = Oversampler::new();
+            oversampler.set_factor(factor);
+            let mut upsampled = Vec::new();
+            let mut through = 0.0f32;
+            for i in 0..SETTLE + MEASURE {
+                let x = (2.0 * PI * freq * i as f32 / SAMPLE_RATE).sin();
+                let y = oversampler.process(x, |u| {
+                    if i >= SETTLE {
+                        upsampled.push(u);
+                    }
+                    u
+                });
+                if i >= SETTLE {
+                    through = through.max(y.abs());
+                }
+            }
+
+            let rate = SAMPLE_RATE * factor as f32;
+            let tone = level(&upsampled, freq, rate);
+            let image = level(&upsampled, SAMPLE_RATE - freq, rate);
+            assert!(
+                (tone - 1.0).abs() < 0.01,
+                "tone at {} for {}x",
+                tone,
+                factor
+            );
+            assert!(image < STOPBAND_GAIN, "image at {} for {}x", image, factor);
+            assert!(
+                (through - 1.0).abs() < 0.01,
+                "round trip at {} for {}x",
+                through,
+                factor
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+pub mod mts;
+pub mod scala;
+
+#[macro_use]
+use crate::parameters;
+use parameters::tuning_param::TuningParam;
+use std::sync::Arc;
+
+use mts::equal_tempered;
+use scala::{KeyboardMapping, Scale};
+
+const MASTER_TUNE_RANGE: f64 = 100.0; // cents either way
+
+// Per-note frequency table, 12-TET with A4 = 440Hz until a Scala scale is
+// loaded or MTS SysEx retunes it. The master tune shifts the whole table.
+pub struct Tuning {
+    param: Arc<TuningParam>,
+    table: [f64; 128],
+}
+
+impl Tuning {
+    pub fn new(param: Arc<TuningParam>) -> Self {
+        let mut tuning = Self {
+            param: param,
+            table: [0.0; 128],
+        };
+        tuning.reset();
+        tuning
+    }
+
+    pub fn default() -> Self {
+        Self::new(Arc::new(TuningParam::default()))
+    }
+
+    pub fn reset(&mut self) {
+        for (note, freq) in self.table.iter_mut().enumerate() {
+            *freq = equal_tempered(note as f64);
+        }
+    }
+
+    // Keys the mapping leaves out keep their 12-TET pitch.
+    pub fn set_scale(&mut self, scale: &Scale, mapping: &KeyboardMapping) {
+        let reference = mapping
+            .note_cents(scale, mapping.reference_note)
+            .unwrap_or_else(|| f64::from(mapping.reference_note - mapping.middle_note) * 100.0);
+        for (note, freq) in self.table.iter_mut().enumerate() {
+            *freq = match mapping.note_cents(scale, note as i32) {
+                Some(cents) => mapping.reference_freq * ((cents - reference) / 1200.0).exp2(),
+                None => equal_tempered(note as f64),
+            };
+        }
+    }
+
+    // Returns whether the message was an MTS tuning message.
+    pub fn process_sysex(&mut self, payload: &[u8]) -> bool {
+        mts::apply(payload, &mut self.table)
+    }
+
+    // Fractional pitches (glide, pitch modulation) interpolate between
+    // neighbouring notes on a log scale, and extrapolate past either end.
+    pub fn freq(&self, pitch: f32) -> f32 {
+        let pitch = f64::from(pitch);
+        let note = pitch.floor().max(0.0).min(126.0);
+        let lower = self.table[note as usize];
+        let upper = self.table[note as usize + 1];
+        let master = (self.param.master_tune.get() * 2.0 - 1.0) as f64 * MASTER_TUNE_RANGE;
+        (lower * (upper / lower).powf(pitch - note) * (master / 1200.0).exp2()) as f32
+    }
+}
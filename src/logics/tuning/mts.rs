@@ -0,0 +1,93 @@
+// MIDI Tuning Standard SysEx messages. Each retunes entries of a 128-note
+// frequency table; messages for other sub-IDs are ignored.
+
+const NON_REALTIME: u8 = 0x7E;
+const REALTIME: u8 = 0x7F;
+const MIDI_TUNING: u8 = 0x08;
+const BULK_DUMP: u8 = 0x01;
+const SINGLE_NOTE: u8 = 0x02;
+const SINGLE_NOTE_BANK: u8 = 0x07;
+const SCALE_OCTAVE: u8 = 0x08;
+
+const NAME_LENGTH: usize = 16;
+const NO_CHANGE: [u8; 3] = [0x7F, 0x7F, 0x7F];
+
+pub fn equal_tempered(semitone: f64) -> f64 {
+    440.0 * ((semitone - 69.0) / 12.0).exp2()
+}
+
+// Three-byte frequency: semitone, then a 14-bit fraction of a semitone.
+fn frequency(data: &[u8]) -> Option<f64> {
+    if data == NO_CHANGE {
+        return None;
+    }
+    let fraction = f64::from(u16::from(data[1]) << 7 | u16::from(data[2])) / 16384.0;
+    Some(equal_tempered(f64::from(data[0]) + fraction))
+}
+
+fn retune_notes(changes: &[u8], count: usize, table: &mut [f64; 128]) {
+    for change in changes.chunks_exact(4).take(count) {
+        if let Some(freq) = frequency(&change[1..4]) {
+            table[usize::from(change[0] & 0x7F)] = freq;
+        }
+    }
+}
+
+// Returns whether `payload` was an MTS message. The F0/F7 framing is optional.
+pub fn apply(payload: &[u8], table: &mut [f64; 128]) -> bool {
+    let start = if payload.first() == Some(&0xF0) { 1 } else { 0 };
+    let end = if payload.last() == Some(&0xF7) {
+        payload.len() - 1
+    } else {
+        payload.len()
+    };
+    if start >= end {
+        return false;
+    }
+    let message = &payload[start..end];
+    if message.len() < 4
+        || (message[0] != NON_REALTIME && message[0] != REALTIME)
+        || message[2] != MIDI_TUNING
+    {
+        return false;
+    }
+
+    let data = &message[4..];
+    match message[3] {
+        // program, name, 128 frequencies, checksum
+        BULK_DUMP => {
+            let frequencies = data.get(1 + NAME_LENGTH..1 + NAME_LENGTH + 128 * 3);
+            match frequencies {
+                Some(frequencies) => {
+                    for (note, bytes) in frequencies.chunks_exact(3).enumerate() {
+                        if let Some(freq) = frequency(bytes) {
+                            table[note] = freq;
+                        }
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+        // program, count, changes
+        SINGLE_NOTE if data.len() >= 2 => {
+            retune_notes(&data[2..], usize::from(data[1]), table);
+            true
+        }
+        // bank, program, count, changes
+        SINGLE_NOTE_BANK if data.len() >= 3 => {
+            retune_notes(&data[3..], usize::from(data[2]), table);
+            true
+        }
+        // channel mask (3 bytes), then a cents offset per pitch class, 64 = none
+        SCALE_OCTAVE if data.len() >= 3 + 12 => {
+            let offsets = &data[3..15];
+            for (note, freq) in table.iter_mut().enumerate() {
+                let cents = f64::from(offsets[note % 12]) - 64.0;
+                *freq = equal_tempered(note as f64 + cents / 100.0);
+            }
+            true
+        }
+        _ => false,
+    }
+}
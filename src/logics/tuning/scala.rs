@@ -0,0 +1,350 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Upper bound on scale and keyboard mapping sizes; real files stay far below it.
+const MAX_SIZE: usize = 1024;
+
+#[derive(Debug)]
+pub enum ScalaError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ScalaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScalaError::Io(err) => write!(f, "{}", err),
+            ScalaError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<io::Error> for ScalaError {
+    fn from(err: io::Error) -> Self {
+        ScalaError::Io(err)
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> ScalaError {
+    ScalaError::Parse {
+        line: line,
+        message: message.to_string(),
+    }
+}
+
+// Non-comment lines with their 1-based line numbers. Scala comments start with '!'.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.starts_with('!'))
+}
+
+// First whitespace-separated token; anything after it is a comment.
+fn first_token(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or("")
+}
+
+fn parse_size(line: usize, token: &str) -> Result<usize, ScalaError> {
+    let size = parse_number(line, token)?;
+    if size > MAX_SIZE {
+        return Err(parse_error(
+            line,
+            &format!("size {} is larger than {}", size, MAX_SIZE),
+        ));
+    }
+    Ok(size)
+}
+
+fn parse_note(line: usize, token: &str) -> Result<i32, ScalaError> {
+    let note = parse_number(line, token)?;
+    if !(0..=127).contains(&note) {
+        return Err(parse_error(
+            line,
+            &format!("note {} is outside 0 to 127", note),
+        ));
+    }
+    Ok(note)
+}
+
+fn parse_number<T: std::str::FromStr>(line: usize, token: &str) -> Result<T, ScalaError> {
+    token
+        .parse()
+        .map_err(|_| parse_error(line, &format!("invalid number '{}'", token)))
+}
+
+// Pitches containing a '.' are cents, anything else is a ratio "n/d" or "n".
+fn parse_pitch(line: usize, token: &str) -> Result<f64, ScalaError> {
+    if token.contains('.') {
+        return parse_number(line, token);
+    }
+    let mut parts = token.splitn(2, '/');
+    let numerator: f64 = parse_number(line, parts.next().unwrap_or(""))?;
+    let denominator: f64 = match parts.next() {
+        Some(part) => parse_number(line, part)?,
+        None => 1.0,
+    };
+    if numerator <= 0.0 || denominator <= 0.0 {
+        return Err(parse_error(line, "ratios must be positive"));
+    }
+    Ok(1200.0 * (numerator / denominator).log2())
+}
+
+// A .scl scale: the cents of each degree above the root, the last being the
+// period (usually the octave).
+pub struct Scale {
+    pub cents: Vec<f64>,
+}
+
+impl Scale {
+    pub fn parse(text: &str) -> Result<Self, ScalaError> {
+        let mut lines = lines(text);
+        lines
+            .next()
+            .ok_or_else(|| parse_error(0, "missing description"))?;
+        let (line, count) = lines
+            .next()
+            .ok_or_else(|| parse_error(0, "missing note count"))?;
+        let count = parse_size(line, first_token(count))?;
+
+        let cents = lines
+            .take(count)
+            .map(|(line, text)| parse_pitch(line, first_token(text)))
+            .collect::<Result<Vec<f64>, ScalaError>>()?;
+        if cents.len() != count {
+            return Err(parse_error(0, "fewer pitches than the note count"));
+        }
+        if count == 0 {
+            return Err(parse_error(0, "a scale needs at least one pitch"));
+        }
+        Ok(Self { cents: cents })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScalaError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cents.len()
+    }
+
+    // Cents of any degree from the root, continuing through further periods.
+    pub fn degree_cents(&self, degree: i32) -> f64 {
+        let len = self.len() as i32;
+        let period = self.cents[self.len() - 1];
+        let periods = degree.div_euclid(len);
+        let step = degree.rem_euclid(len);
+        let within = if step == 0 {
+            0.0
+        } else {
+            self.cents[step as usize - 1]
+        };
+        f64::from(periods) * period + within
+    }
+}
+
+// A .kbm keyboard mapping. An empty mapping assigns consecutive keys to
+// consecutive scale degrees.
+pub struct KeyboardMapping {
+    pub first_note: i32,
+    pub last_note: i32,
+    pub middle_note: i32, // key of scale degree 0
+    pub reference_note: i32,
+    pub reference_freq: f64,
+    pub octave_degree: i32,        // scale degree the mapping repeats at
+    pub mapping: Vec<Option<i32>>, // None leaves a key unmapped
+}
+
+impl KeyboardMapping {
+    // Standard 12-note keyboard, A4 = 440Hz, for a scale of `scale_len` degrees.
+    pub fn linear(scale_len: usize) -> Self {
+        Self {
+            first_note: 0,
+            last_note: 127,
+            middle_note: 60,
+            reference_note: 69,
+            reference_freq: 440.0,
+            octave_degree: scale_len as i32,
+            mapping: Vec::new(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ScalaError> {
+        let mut lines = lines(text).filter(|(_, line)| !line.is_empty());
+        let mut next = |name: &str| {
+            lines
+                .next()
+                .ok_or_else(|| parse_error(0, &format!("missing {}", name)))
+        };
+
+        let (line, text) = next("map size")?;
+        let size = parse_size(line, first_token(text))?;
+        let (line, text) = next("first note")?;
+        let first_note = parse_number(line, first_token(text))?;
+        let (line, text) = next("last note")?;
+        let last_note = parse_number(line, first_token(text))?;
+        let (line, text) = next("middle note")?;
+        let middle_note = parse_note(line, first_token(text))?;
+        let (line, text) = next("reference note")?;
+        let reference_note = parse_note(line, first_token(text))?;
+        let (line, text) = next("reference frequency")?;
+        let reference_freq: f64 = parse_number(line, first_token(text))?;
+        if !(reference_freq.is_finite() && reference_freq > 0.0) {
+            return Err(parse_error(line, "reference frequency must be positive"));
+        }
+        let (line, text) = next("octave degree")?;
+        let octave_degree = parse_number(line, first_token(text))?;
+
+        let mut mapping = lines
+            .take(size)
+            .map(|(line, text)| match first_token(text) {
+                "x" | "X" => Ok(None),
+                token => parse_number(line, token).map(Some),
+            })
+            .collect::<Result<Vec<Option<i32>>, ScalaError>>()?;
+        // Trailing mapping lines may be left out; those keys are unmapped.
+        mapping.resize(size, None);
+
+        Ok(Self {
+            first_note: first_note,
+            last_note: last_note,
+            middle_note: middle_note,
+            reference_note: reference_note,
+            reference_freq: reference_freq,
+            octave_degree: octave_degree,
+            mapping: mapping,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScalaError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    // Cents of `note` from scale degree 0, or None for an unmapped key.
+    pub fn note_cents(&self, scale: &Scale, note: i32) -> Option<f64> {
+        if note < self.first_note || note > self.last_note {
+            return None;
+        }
+        let offset = note - self.middle_note;
+        if self.mapping.is_empty() {
+            return Some(scale.degree_cents(offset));
+        }
+        let size = self.mapping.len() as i32;
+        let repeats = offset.div_euclid(size);
+        let degree = self.mapping[offset.rem_euclid(size) as usize]?;
+        // An octave degree of 0 means the scale's own period.
+        let octave_degree = if self.octave_degree > 0 {
+            self.octave_degree
+        } else {
+            scale.len() as i32
+        };
+        Some(f64::from(repeats) * scale.degree_cents(octave_degree) + scale.degree_cents(degree))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_cents(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1.0e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_cents_ratios_and_comments() {
+        let scale = Scale::parse(
+            "! meantone.scl\n\
+             !\n\
+             Test scale\n \
+             4\n\
+             ! the degrees\n\
+             100.0\n\
+             3/2 fifth\n\
+             -5.5\n\
+             2\n",
+        )
+        .unwrap();
+        assert_eq!(scale.len(), 4);
+        assert_cents(scale.cents[0], 100.0);
+        assert_cents(scale.cents[1], 1200.0 * 1.5f64.log2());
+        assert_cents(scale.cents[2], -5.5);
+        assert_cents(scale.cents[3], 1200.0);
+        assert_cents(scale.degree_cents(5), 1300.0);
+        assert_cents(scale.degree_cents(-3), -1100.0);
+    }
+
+    #[test]
+    fn rejects_malformed_scales() {
+        assert!(Scale::parse("").is_err());
+        assert!(Scale::parse("Description\n").is_err());
+        assert!(Scale::parse("Description\nmany\n").is_err());
+        assert!(Scale::parse("Description\n0\n").is_err());
+        assert!(Scale::parse("Description\n2\n100.0\n").is_err());
+        assert!(Scale::parse("Description\n1\n3/0\n").is_err());
+        assert!(Scale::parse("Description\n1\nfifth\n").is_err());
+    }
+
+    #[test]
+    fn rejects_huge_counts() {
+        assert!(Scale::parse("Description\n18446744073709551615\n2/1\n").is_err());
+        assert!(Scale::parse("Description\n1025\n2/1\n").is_err());
+        let kbm = "18446744073709551615\n0\n127\n60\n69\n440.0\n12\n";
+        assert!(KeyboardMapping::parse(kbm).is_err());
+    }
+
+    #[test]
+    fn parses_keyboard_mapping_with_unmapped_and_missing_entries() {
+        let mapping = KeyboardMapping::parse(
+            "! white keys only\n\
+             5\n\
+             0\n\
+             127\n\
+             60\n\
+             69\n\
+             440.0\n\
+             3\n\
+             ! mapping\n\
+             0\n\
+             x\n\
+             1\n",
+        )
+        .unwrap();
+        assert_eq!(mapping.middle_note, 60);
+        assert_eq!(mapping.reference_freq, 440.0);
+        assert_eq!(mapping.mapping, vec![Some(0), None, Some(1), None, None]);
+
+        let scale = Scale::parse("Thirds\n3\n400.0\n800.0\n2/1\n").unwrap();
+        assert_cents(mapping.note_cents(&scale, 62).unwrap(), 400.0);
+        assert!(mapping.note_cents(&scale, 61).is_none());
+        assert_cents(mapping.note_cents(&scale, 65).unwrap(), 1200.0);
+    }
+
+    #[test]
+    fn rejects_keyboard_mappings_with_invalid_reference() {
+        let kbm = |middle: &str, reference: &str, freq: &str| {
+            format!("0\n0\n127\n{}\n{}\n{}\n12\n", middle, reference, freq)
+        };
+        assert!(KeyboardMapping::parse(&kbm("60", "69", "440.0")).is_ok());
+        for freq in ["0", "0.0", "-440.0", "NaN", "inf", "-inf"].iter() {
+            assert!(KeyboardMapping::parse(&kbm("60", "69", freq)).is_err());
+        }
+        for note in ["-1", "128", "-2147483648", "2147483647"].iter() {
+            assert!(KeyboardMapping::parse(&kbm(note, "69", "440.0")).is_err());
+            assert!(KeyboardMapping::parse(&kbm("60", note, "440.0")).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_keyboard_mappings() {
+        assert!(KeyboardMapping::parse("12\n0\n127\n").is_err());
+        let bad_entry = "2\n0\n127\n60\n69\n440.0\n12\n0\nseven\n";
+        assert!(KeyboardMapping::parse(bad_entry).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+#[macro_use]
+use crate::parameters;
+use parameters::unison_param::UnisonParam;
+use parameters::vco_param::VCOParam;
+use std::sync::Arc;
+
+use super::vco::{PhaseReset, VCO};
+
+pub const MAX_VOICES: usize = 8;
+const DETUNE_RANGE: f32 = 50.0; // cents from the centre to the outermost voice
+const GOLDEN_RATIO_FRACTION: f32 = 0.618_034;
+const SPREAD_DELAY: f32 = 0.011; // seconds, short enough to fuse into one image
+const MAX_SPREAD_SIDE: f32 = 0.5; // side level at full spread
+
+pub fn voices_from_f32(value: f32) -> usize {
+    (value * (MAX_VOICES - 1) as f32).round() as usize + 1
+}
+
+// Stacks detuned copies of the VCO for one note. All voices are allocated up
+// front and only the active ones are ticked, so changing the count never
+// allocates on the audio thread.
+pub struct Unison {
+    param: Arc<UnisonParam>,
+    vco_param: Arc<VCOParam>,
+    voices: Vec<VCO>,
+    active: usize,
+}
+
+impl Unison {
+    pub fn new(param: Arc<UnisonParam>, vco_param: Arc<VCOParam>, sample_rate: f32) -> Self {
+        let mut voices = Vec::with_capacity(MAX_VOICES);
+        for i in 0..MAX_VOICES {
+            let mut voice = VCO::new(vco_param.clone(), sample_rate);
+            voice.reset_phase(Self::start_phase(i));
+            voices.push(voice);
+        }
+        Self {
+            param: param,
+            vco_param: vco_param,
+            voices: voices,
+            active: 1,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self::new(
+            Arc::new(UnisonParam::default()),
+            Arc::new(VCOParam::default()),
+            44100.0,
+        )
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_sample_rate(sample_rate);
+        }
+    }
+
+    pub fn mod_pw(&mut self, env: f32, lfo: f32, offset: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.mod_pw(env, lfo, offset);
+        }
+    }
+
+    pub fn mod_levels(&mut self, saw: f32, tri: f32, squ: f32, sub: f32, noise: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.mod_levels(saw, tri, squ, sub, noise);
+        }
+    }
+
+    // Each voice gets its own seed so their noise stays uncorrelated.
+    pub fn seed(&mut self, seed: u64) {
+        for (i, voice) in self.voices.iter_mut().enumerate() {
+            voice.seed(seed.wrapping_add(i as u64));
+        }
+    }
+
+    // Irregular start phases, so stacked voices neither begin in lockstep nor
+    // cancel each other's harmonics as evenly spaced phases would.
+    fn start_phase(voice: usize) -> f32 {
+        voice as f32 * GOLDEN_RATIO_FRACTION
+    }
+
+    // Applies the phase reset mode at the start of a note. Reset to zero puts
+    // every voice back at its fixed start phase, so each note starts the same.
+    // The analog per-note phase offset only moves free-running voices, as it
+    // would otherwise undo the reset.
+    pub fn note_on(&mut self, phase_offset: f32) {
+        match PhaseReset::from_f32(self.vco_param.phase_reset.get()) {
+            PhaseReset::FREE => self.shift_phase(phase_offset),
+            PhaseReset::ZERO => {
+                for (i, voice) in self.voices.iter_mut().enumerate() {
+                    voice.reset_phase(Self::start_phase(i));
+                }
+            }
+            PhaseReset::RANDOM => {
+                for voice in self.voices.iter_mut() {
+                    voice.randomize_phase();
+                }
+            }
+        }
+    }
+
+    fn shift_phase(&mut self, shift: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.shift_phase(shift);
+        }
+    }
+
+    // Position of voice `i` across the stack, from -1 to 1.
+    fn position(&self, i: usize) -> f32 {
+        if self.active == 1 {
+            0.0
+        } else {
+            i as f32 / (self.active - 1) as f32 * 2.0 - 1.0
+        }
+    }
+
+    // Detuned voices are largely uncorrelated, so the sum is scaled by
+    // 1/sqrt(n) to keep the loudness steady as voices are added.
+    pub fn tick(&mut self, freq: f32) -> f32 {
+        self.active = voices_from_f32(self.param.voices.get());
+
+        if self.active == 1 {
+            return self.voices[0].tick(freq);
+        }
+
+        let detune = self.param.detune.get() * DETUNE_RANGE / 1200.0;
+        let gain = 1.0 / (self.active as f32).sqrt();
+        let mut out = 0.0;
+        for i in 0..self.active {
+            let position = self.position(i);
+            out += self.voices[i].tick(freq * (position * detune).exp2()) * gain;
+        }
+        out
+    }
+}
+
+// Stereo spread for the unison stack. The voices share the one VCF, so the
+// stack is widened after it: a delayed copy of the filtered signal is added
+// to one side and subtracted from the other. The side cancels when folded to
+// mono, leaving the filtered sum untouched.
+pub struct Spread {
+    param: Arc<UnisonParam>,
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl Spread {
+    pub fn new(param: Arc<UnisonParam>, sample_rate: f32) -> Self {
+        Self {
+            param: param,
+            buffer: vec![0.0; Self::length(sample_rate)],
+            position: 0,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self::new(Arc::new(UnisonParam::default()), 44100.0)
+    }
+
+    fn length(sample_rate: f32) -> usize {
+        (SPREAD_DELAY * sample_rate) as usize + 1
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.buffer = vec![0.0; Self::length(sample_rate)];
+        self.position = 0;
+    }
+
+    pub fn process(&mut self, x: f32) -> (f32, f32) {
+        // The buffer holds exactly the delay, so the oldest sample is the tap.
+        let delayed = self.buffer[self.position];
+        self.buffer[self.position] = x;
+        self.position = (self.position + 1) % self.buffer.len();
+
+        let voices = voices_from_f32(self.param.voices.get());
+        if voices == 1 {
+            return (x, x);
+        }
+        let side = delayed * self.param.spread.get() * MAX_SPREAD_SIDE;
+        (x + side, x - side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZERO: f32 = 0.5;
+    const RANDOM: f32 = 1.0;
+    const SAMPLES: usize = 256;
+
+    fn unison(phase_reset: f32) -> Unison {
+        let param = UnisonParam::default();
+        param.voices.set(1.0);
+        let vco_param = VCOParam::default();
+        vco_param.phase_reset.set(phase_reset);
+        vco_param.squ_rate.set(1.0);
+        vco_param.sub_rate.set(1.0);
+        Unison::new(Arc::new(param), Arc::new(vco_param), 44100.0)
+    }
+
+    fn render(unison: &mut Unison, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| unison.tick(110.0)).collect()
+    }
+
+    #[test]
+    fn zero_reset_starts_every_note_the_same() {
+        let mut first = unison(ZERO);
+        let mut second = unison(ZERO);
+        render(&mut first, 1000);
+        render(&mut second, 1789);
+
+        first.note_on(0.3);
+        second.note_on(0.7);
+        assert_eq!(render(&mut first, SAMPLES), render(&mut second, SAMPLES));
+    }
+
+    #[test]
+    fn random_reset_follows_the_seed() {
+        let mut first = unison(RANDOM);
+        let mut second = unison(RANDOM);
+        first.seed(7);
+        second.seed(7);
+        render(&mut first, 1000);
+        render(&mut second, 1000);
+
+        first.note_on(0.0);
+        second.note_on(0.0);
+        let first_note = render(&mut first, SAMPLES);
+        assert_eq!(first_note, render(&mut second, SAMPLES));
+
+        // A different seed lands on different phases.
+        let mut third = unison(RANDOM);
+        third.seed(8);
+        render(&mut third, 1000);
+        third.note_on(0.0);
+        assert_ne!(first_note, render(&mut third, SAMPLES));
+    }
+}
@@ -1,9 +1,130 @@
 #[macro_use]
 use crate::parameters;
 use parameters::vcf_param::VCFParam;
-use std::f32::consts::PI;
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
 use std::sync::Arc;
 
+// Cutoff CV is handled in octaves above MIN_CUTOFF, like a 1V/oct control input.
+const MIN_CUTOFF: f32 = 20.0;
+const CUTOFF_RANGE: f32 = 10.0; // octaves covered by the cutoff knob
+const ENV_RANGE: f32 = 8.0; // octaves of envelope sweep at full (+/-) amount
+const KBD_CENTER_FREQ: f32 = 261.625_57; // C4, keyboard follow pivot
+const MAX_CUTOFF_RATIO: f32 = 0.45; // of sample rate
+
+// The ladder peaks at 1/sqrt(2) of the stage cutoff and needs a loop gain of 17
+// there to self-oscillate, so stages are tuned up to put the peak on the cutoff.
+const PEAK_RATIO: f32 = FRAC_1_SQRT_2;
+const SELF_OSC_FEEDBACK: f32 = 17.0;
+const RESONANCE_OVERDRIVE: f32 = 1.1; // max resonance sits just past self-oscillation
+const MAX_STAGE_CUTOFF_RATIO: f32 = 0.49; // of sample rate, keeps the prewarp finite
+
+const NEWTON_ITERATIONS: i32 = 4;
+const NEWTON_TOLERANCE: f32 = 1.0e-6;
+
+// A cascade of four identical one-poles peaks at the cutoff with a loop gain of 4.
+const LADDER_SELF_OSC_FEEDBACK: f32 = 4.0;
+const MIN_SVF_DAMPING: f32 = 0.01;
+// Resonant peak gain the linear SVF lowpass/highpass may reach, about what
+// the saturating OTA core settles at.
+const MAX_SVF_PEAK: f32 = 2.0;
+
+pub trait FilterModel {
+    fn set_sample_rate(&mut self, sample_rate: f32);
+    fn set_fc(&mut self, fc: f32);
+    fn set_k(&mut self, k: f32);
+    fn set_compensation(&mut self, _compensation: GainCompensation) {}
+    fn filter(&mut self, x: f32) -> f32;
+}
+
+pub enum FilterModelType {
+    DIODE_LADDER,
+    TRANSISTOR_LADDER,
+    OTA_2_POLE,
+    OTA_4_POLE,
+    STATE_VARIABLE,
+}
+
+impl FilterModelType {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 4.0).round() as i32 {
+            0 => Self::DIODE_LADDER,
+            1 => Self::TRANSISTOR_LADDER,
+            2 => Self::OTA_2_POLE,
+            3 => Self::OTA_4_POLE,
+            _ => Self::STATE_VARIABLE,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SVFMode {
+    LOWPASS,
+    BANDPASS,
+    HIGHPASS,
+    NOTCH,
+}
+
+impl SVFMode {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 3.0).round() as i32 {
+            0 => Self::LOWPASS,
+            1 => Self::BANDPASS,
+            2 => Self::HIGHPASS,
+            _ => Self::NOTCH,
+        }
+    }
+}
+
+pub fn clip(x: f32) -> f32 {
+    1.0 / (1.0 as f32).tanh() * x.tanh()
+}
+
+fn clip_derivative(x: f32) -> f32 {
+    let t = x.tanh();
+    (1.0 - t * t) / (1.0 as f32).tanh()
+}
+
+#[derive(Clone, Copy)]
+pub enum GainCompensation {
+    OFF,
+    HALF,
+    FULL,
+}
+
+impl GainCompensation {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::OFF,
+            1 => Self::HALF,
+            _ => Self::FULL,
+        }
+    }
+
+    fn ratio(&self) -> f32 {
+        match self {
+            Self::OFF => 0.0,
+            Self::HALF => 0.5,
+            Self::FULL => 1.0,
+        }
+    }
+}
+
+pub enum FilterQuality {
+    ECONOMY,   // unit-delayed feedback between stages
+    ZDF,       // zero-delay-feedback linear solve, saturated afterwards
+    NONLINEAR, // zero-delay-feedback with the input saturator solved by Newton-Raphson
+}
+
+impl FilterQuality {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::ECONOMY,
+            1 => Self::ZDF,
+            _ => Self::NONLINEAR,
+        }
+    }
+}
+
 struct LPF1 {
     sample_rate: f32,
     cutoff: f32,
@@ -29,10 +150,19 @@ impl LPF1 {
         self.sample_rate = sample_rate;
     }
 
-    fn filter(&mut self, x: f32) -> f32 {
-        let wa = 2.0 * self.sample_rate * (PI * self.cutoff / self.sample_rate).tanh();
+    fn gain(&self) -> f32 {
+        let wa = 2.0 * self.sample_rate * (PI * self.cutoff / self.sample_rate).tan();
         let g = wa / (2.0 * self.sample_rate);
-        let G = g / (1.0 + g);
+        g / (1.0 + g)
+    }
+
+    // Output for a zero input this sample, i.e. y = gain() * x + response().
+    fn response(&self) -> f32 {
+        (1.0 - self.gain()) * (self.yz1 + self.uz1)
+    }
+
+    fn filter(&mut self, x: f32) -> f32 {
+        let G = self.gain();
         let v = x - self.yz1 - self.uz1;
         let u = G * v;
         let y = u + self.uz1 + self.yz1;
@@ -95,6 +225,45 @@ impl DiodeLadder {
         self.yz1 = y;
         self.yz1
     }
+
+    // Resolves the stage couplings without delays, returning (c1, d1, c2, d2, c3, d3)
+    // such that y1 = c1 * x + d1, y2 = c2 * y1 + d2 and y3 = c3 * y2 + d3.
+    fn zdf_coefficients(&self) -> (f32, f32, f32, f32, f32, f32) {
+        let g = self.lpf1.gain();
+        let a = g / 2.0;
+        let c3 = a / (1.0 - a * a);
+        let d3 = (a * self.lpf4.response() + self.lpf3.response()) / (1.0 - a * a);
+        let c2 = a / (1.0 - a * c3);
+        let d2 = (a * d3 + self.lpf2.response()) / (1.0 - a * c3);
+        let c1 = g / (1.0 - g * c2);
+        let d1 = (g * d2 + self.lpf1.response()) / (1.0 - g * c2);
+        (c1, d1, c2, d2, c3, d3)
+    }
+
+    // Instantaneous output response (m, n) such that y = m * x + n.
+    fn zdf_response(&self) -> (f32, f32) {
+        let (c1, d1, c2, d2, c3, d3) = self.zdf_coefficients();
+        let a = self.lpf4.gain() / 2.0;
+        let m = a * c3 * c2 * c1;
+        let n = a * (c3 * (c2 * d1 + d2) + d3) + self.lpf4.response();
+        (m, n)
+    }
+
+    fn filter_zdf(&mut self, x: f32) -> f32 {
+        let (c1, d1, c2, d2, c3, d3) = self.zdf_coefficients();
+        let y1 = c1 * x + d1;
+        let y2 = c2 * y1 + d2;
+        let y3 = c3 * y2 + d3;
+        let y4 = self.lpf4.response() + self.lpf4.gain() * y3 / 2.0;
+        self.lpf1.filter(x + y2);
+        self.lpf2.filter((y1 + y3) / 2.0);
+        self.lpf3.filter((y2 + y4) / 2.0);
+        let y = self.lpf4.filter(y3 / 2.0);
+        self.y2z1 = y2;
+        self.y3z1 = y3;
+        self.yz1 = y;
+        self.yz1
+    }
 }
 
 struct DiodeLadderLPF {
@@ -103,7 +272,9 @@ struct DiodeLadderLPF {
     sample_rate: f32,
     cutoff: f32,
     k: f32,
-    A: f32,
+    compensation: GainCompensation,
+    quality: FilterQuality,
+    uz1: f32,
 }
 
 impl DiodeLadderLPF {
@@ -114,13 +285,44 @@ impl DiodeLadderLPF {
             k: 0.0,
             sample_rate: 44100.0,
             yz1: 0.0,
-            A: 1.0,
+            compensation: GainCompensation::HALF,
+            quality: FilterQuality::ZDF,
+            uz1: 0.0,
         }
     }
 
+    fn set_quality(&mut self, quality: FilterQuality) {
+        self.quality = quality;
+    }
+
+    fn clip(&self, x: f32) -> f32 {
+        clip(x)
+    }
+
+    fn clip_derivative(&self, x: f32) -> f32 {
+        clip_derivative(x)
+    }
+
+    // Solves u = clip(x - feedback * (m * u + n)) for the ladder input u.
+    fn solve_input(&self, x: f32, feedback: f32, m: f32, n: f32) -> f32 {
+        let mut u = self.uz1;
+        for _ in 0..NEWTON_ITERATIONS {
+            let v = x - feedback * (m * u + n);
+            let residue = u - self.clip(v);
+            if residue.abs() < NEWTON_TOLERANCE {
+                break;
+            }
+            u -= residue / (1.0 + feedback * m * self.clip_derivative(v));
+        }
+        u
+    }
+}
+
+impl FilterModel for DiodeLadderLPF {
     fn set_fc(&mut self, fc: f32) {
         self.cutoff = fc;
-        self.diode_ladder.set_fc(fc);
+        let stage_fc = (fc / PEAK_RATIO).min(self.sample_rate * MAX_STAGE_CUTOFF_RATIO);
+        self.diode_ladder.set_fc(stage_fc);
     }
 
     fn set_sample_rate(&mut self, sample_rate: f32) {
@@ -132,16 +334,189 @@ impl DiodeLadderLPF {
         self.k = k;
     }
 
-    fn clip(&self, x: f32) -> f32 {
-        1.0 / (1.0 as f32).tanh() * x.tanh()
+    fn set_compensation(&mut self, compensation: GainCompensation) {
+        self.compensation = compensation;
+    }
+
+    fn filter(&mut self, x: f32) -> f32 {
+        let feedback = self.k * SELF_OSC_FEEDBACK * RESONANCE_OVERDRIVE;
+        // Saturating the summed ladder input bounds the loop, so self-oscillation
+        // settles at a fixed level instead of blowing up.
+        let u = match self.quality {
+            FilterQuality::ECONOMY => self.clip(x - feedback * self.yz1),
+            FilterQuality::ZDF => {
+                let (m, n) = self.diode_ladder.zdf_response();
+                self.clip((x - feedback * n) / (1.0 + feedback * m))
+            }
+            FilterQuality::NONLINEAR => {
+                let (m, n) = self.diode_ladder.zdf_response();
+                self.solve_input(x, feedback, m, n)
+            }
+        };
+        self.uz1 = u;
+        self.yz1 = match self.quality {
+            FilterQuality::ECONOMY => self.diode_ladder.filter(u),
+            _ => self.diode_ladder.filter_zdf(u),
+        };
+        let gain = 1.0 + feedback * self.compensation.ratio();
+        gain * self.yz1
+    }
+}
+
+// Moog-style ladder: four buffered one-poles with a zero-delay feedback solve.
+// With `ota` set every stage input is saturated as well, which gives the
+// softer, grittier OTA cascade character instead of a single input saturator.
+struct TransistorLadderLPF {
+    lpf1: LPF1,
+    lpf2: LPF1,
+    lpf3: LPF1,
+    lpf4: LPF1,
+    sample_rate: f32,
+    k: f32,
+    compensation: GainCompensation,
+    ota: bool,
+}
+
+impl TransistorLadderLPF {
+    fn new(ota: bool) -> Self {
+        Self {
+            lpf1: LPF1::new(),
+            lpf2: LPF1::new(),
+            lpf3: LPF1::new(),
+            lpf4: LPF1::new(),
+            sample_rate: 44100.0,
+            k: 0.0,
+            compensation: GainCompensation::HALF,
+            ota: ota,
+        }
+    }
+
+    fn stage_input(&self, x: f32) -> f32 {
+        if self.ota {
+            x.tanh()
+        } else {
+            x
+        }
+    }
+}
+
+impl FilterModel for TransistorLadderLPF {
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.lpf1.set_sample_rate(sample_rate);
+        self.lpf2.set_sample_rate(sample_rate);
+        self.lpf3.set_sample_rate(sample_rate);
+        self.lpf4.set_sample_rate(sample_rate);
+    }
+
+    fn set_fc(&mut self, fc: f32) {
+        let stage_fc = fc.min(self.sample_rate * MAX_STAGE_CUTOFF_RATIO);
+        self.lpf1.set_fc(stage_fc);
+        self.lpf2.set_fc(stage_fc);
+        self.lpf3.set_fc(stage_fc);
+        self.lpf4.set_fc(stage_fc);
+    }
+
+    fn set_k(&mut self, k: f32) {
+        self.k = k;
+    }
+
+    fn set_compensation(&mut self, compensation: GainCompensation) {
+        self.compensation = compensation;
     }
 
     fn filter(&mut self, x: f32) -> f32 {
-        self.A = 1.0 + 0.5 * self.k * 20.0;
-        let clipped_input = self.clip(x);
-        let feedback = self.k * 14.0 * self.yz1;
-        self.yz1 = self.diode_ladder.filter(clipped_input - feedback);
-        self.A * self.yz1
+        let feedback = self.k * LADDER_SELF_OSC_FEEDBACK * RESONANCE_OVERDRIVE;
+        let g = self.lpf1.gain();
+        let m = g * g * g * g;
+        let n = g * (g * (g * self.lpf1.response() + self.lpf2.response()) + self.lpf3.response())
+            + self.lpf4.response();
+        let u = clip((x - feedback * n) / (1.0 + feedback * m));
+        let y1 = self.lpf1.filter(u);
+        let y2 = self.lpf2.filter(self.stage_input(y1));
+        let y3 = self.lpf3.filter(self.stage_input(y2));
+        let y = self.lpf4.filter(self.stage_input(y3));
+        let gain = 1.0 + feedback * self.compensation.ratio();
+        gain * y
+    }
+}
+
+// Topology-preserving state variable filter. With `saturate` set both integrators
+// are driven through a soft clipper, modelling the transconductors of a 2-pole OTA-C core.
+struct StateVariableFilter {
+    sample_rate: f32,
+    cutoff: f32,
+    k: f32,
+    mode: SVFMode,
+    saturate: bool,
+    s1: f32,
+    s2: f32,
+}
+
+impl StateVariableFilter {
+    fn new(saturate: bool) -> Self {
+        Self {
+            sample_rate: 44100.0,
+            cutoff: 1000.0,
+            k: 0.0,
+            mode: SVFMode::LOWPASS,
+            saturate: saturate,
+            s1: 0.0,
+            s2: 0.0,
+        }
+    }
+
+    fn set_mode(&mut self, mode: SVFMode) {
+        self.mode = mode;
+    }
+
+    fn integrator_input(&self, x: f32) -> f32 {
+        if self.saturate {
+            x.tanh()
+        } else {
+            x
+        }
+    }
+}
+
+impl FilterModel for StateVariableFilter {
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    fn set_fc(&mut self, fc: f32) {
+        self.cutoff = fc.min(self.sample_rate * MAX_STAGE_CUTOFF_RATIO);
+    }
+
+    fn set_k(&mut self, k: f32) {
+        self.k = k;
+    }
+
+    fn filter(&mut self, x: f32) -> f32 {
+        let g = (PI * self.cutoff / self.sample_rate).tan();
+        let r = (1.0 - self.k).max(MIN_SVF_DAMPING);
+        let hp = (x - (2.0 * r + g) * self.s1 - self.s2) / (1.0 + 2.0 * r * g + g * g);
+        let v1 = g * self.integrator_input(hp);
+        let bp = v1 + self.s1;
+        self.s1 = v1 + bp;
+        let v2 = g * self.integrator_input(bp);
+        let lp = v2 + self.s2;
+        self.s2 = v2 + lp;
+        // Linear lowpass and highpass peak at 1/2r around the cutoff, +34dB at
+        // full resonance. Once that passes MAX_SVF_PEAK they are turned down
+        // to hold the peak there; band outputs are already normalised by 2r,
+        // and the saturating core limits its own peak.
+        let peak_compensation = if self.saturate {
+            1.0
+        } else {
+            (2.0 * r * MAX_SVF_PEAK).min(1.0)
+        };
+        match self.mode {
+            SVFMode::LOWPASS => peak_compensation * lp,
+            SVFMode::BANDPASS => 2.0 * r * bp,
+            SVFMode::HIGHPASS => peak_compensation * hp,
+            SVFMode::NOTCH => x - 2.0 * r * bp,
+        }
     }
 }
 
@@ -149,15 +524,27 @@ pub struct VCF {
     sample_rate: f32,
     param: Arc<VCFParam>,
     moded_fc: f32,
+    cutoff_mod: f32,
+    resonance_mod: f32,
     lpf: DiodeLadderLPF,
+    transistor_ladder: TransistorLadderLPF,
+    ota_2_pole: StateVariableFilter,
+    ota_4_pole: TransistorLadderLPF,
+    svf: StateVariableFilter,
 }
 
 impl VCF {
     pub fn new(param: Arc<VCFParam>, sample_rate: f32) -> Self {
         Self {
             sample_rate: sample_rate,
+            cutoff_mod: 0.0,
+            resonance_mod: 0.0,
             lpf: DiodeLadderLPF::new(),
-            moded_fc: param.cutoff.get(),
+            transistor_ladder: TransistorLadderLPF::new(false),
+            ota_2_pole: StateVariableFilter::new(true),
+            ota_4_pole: TransistorLadderLPF::new(true),
+            svf: StateVariableFilter::new(false),
+            moded_fc: MIN_CUTOFF * (param.cutoff.get() * CUTOFF_RANGE).exp2(),
             param: param,
         }
     }
@@ -166,25 +553,196 @@ impl VCF {
         let param = Arc::new(VCFParam::default());
         Self {
             sample_rate: 44100.0,
+            cutoff_mod: 0.0,
+            resonance_mod: 0.0,
             lpf: DiodeLadderLPF::new(),
-            moded_fc: param.cutoff.get(),
+            transistor_ladder: TransistorLadderLPF::new(false),
+            ota_2_pole: StateVariableFilter::new(true),
+            ota_4_pole: TransistorLadderLPF::new(true),
+            svf: StateVariableFilter::new(false),
+            moded_fc: MIN_CUTOFF * (param.cutoff.get() * CUTOFF_RANGE).exp2(),
             param: param,
         }
     }
 
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    // Extra cutoff (in octaves) and resonance offsets, e.g. from the mod matrix.
+    pub fn modulate(&mut self, cutoff: f32, resonance: f32) {
+        self.cutoff_mod = cutoff;
+        self.resonance_mod = resonance;
+    }
+
     pub fn mod_fc(&mut self, cv: f32, note: f32) {
-        self.moded_fc = self.param.cutoff.get() * self.sample_rate / 2.0
-            + (self.sample_rate / 2.0 - self.param.cutoff.get() * self.sample_rate / 2.0)
-                * cv
-                * self.param.cutoff_mod.get()
-            + note * 10.0 * self.param.kbd_follow.get()
+        let base = self.param.cutoff.get() * CUTOFF_RANGE;
+        let env_amount = (self.param.cutoff_mod.get() * 2.0 - 1.0) * ENV_RANGE;
+        let kbd = (note / KBD_CENTER_FREQ).log2() * self.param.kbd_follow.get();
+        let octave = base + env_amount * cv + kbd + self.cutoff_mod;
+        self.moded_fc = (MIN_CUTOFF * octave.exp2())
+            .min(self.sample_rate * MAX_CUTOFF_RATIO)
+            .max(MIN_CUTOFF);
+    }
+
+    fn model(&mut self) -> &mut dyn FilterModel {
+        match FilterModelType::from_f32(self.param.model.get()) {
+            FilterModelType::DIODE_LADDER => {
+                self.lpf
+                    .set_quality(FilterQuality::from_f32(self.param.quality.get()));
+                &mut self.lpf
+            }
+            FilterModelType::TRANSISTOR_LADDER => &mut self.transistor_ladder,
+            FilterModelType::OTA_2_POLE => &mut self.ota_2_pole,
+            FilterModelType::OTA_4_POLE => &mut self.ota_4_pole,
+            FilterModelType::STATE_VARIABLE => {
+                self.svf
+                    .set_mode(SVFMode::from_f32(self.param.svf_mode.get()));
+                &mut self.svf
+            }
+        }
     }
 
     pub fn filter(&mut self, x: f32) -> f32 {
-        self.lpf.set_sample_rate(self.sample_rate);
-        self.lpf.set_fc(self.moded_fc);
-        self.lpf.set_k(self.param.k.get());
+        let sample_rate = self.sample_rate;
+        let fc = self.moded_fc;
+        let k = (self.param.k.get() + self.resonance_mod).max(0.0).min(1.0);
+        let compensation = GainCompensation::from_f32(self.param.compensation.get());
+
+        let lpf = self.model();
+        lpf.set_sample_rate(sample_rate);
+        lpf.set_fc(fc);
+        lpf.set_k(k);
+        lpf.set_compensation(compensation);
+
+        lpf.filter(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATES: [f32; 4] = [44100.0, 48000.0, 96000.0, 192000.0];
+    const MAX_MODEL_PEAK: f32 = 2.1; // +6dB, with a little headroom
+
+    // Cutoff knob value for `fc`, from fc = MIN_CUTOFF * 2^(cutoff * CUTOFF_RANGE).
+    fn cutoff_for(fc: f32) -> f32 {
+        (fc / MIN_CUTOFF).log2() / CUTOFF_RANGE
+    }
+
+    fn self_oscillating(fc: f32, quality: f32, sample_rate: f32) -> VCF {
+        let param = VCFParam::default();
+        param.cutoff.set(cutoff_for(fc));
+        param.k.set(1.0);
+        param.kbd_follow.set(1.0);
+        param.quality.set(quality);
+        let mut vcf = VCF::new(Arc::new(param), sample_rate);
+        vcf.mod_fc(0.5, KBD_CENTER_FREQ); // half env cv, no envelope amount
+        vcf
+    }
+
+    // Kicks the filter with an impulse, lets it settle, then measures the
+    // frequency from zero crossings over one second while checking the output
+    // stays finite and bounded.
+    fn oscillation_freq(vcf: &mut VCF, sample_rate: f32) -> f32 {
+        let settle = (sample_rate * 2.0) as usize;
+        let measure = sample_rate as usize;
+        let mut previous = vcf.filter(1.0);
+        let mut crossings = 0;
+        for i in 0..settle + measure {
+            let y = vcf.filter(0.0);
+            assert!(y.is_finite(), "output is not finite at sample {}", i);
+            assert!(y.abs() < 40.0, "output {} runs away at sample {}", y, i);
+            if i >= settle && (previous < 0.0) != (y < 0.0) {
+                crossings += 1;
+            }
+            previous = y;
+        }
+        crossings as f32 / 2.0
+    }
 
-        self.lpf.filter(x)
+    #[test]
+    fn max_resonance_is_stable_and_self_oscillates_at_cutoff() {
+        for &sample_rate in SAMPLE_RATES.iter() {
+            // The economy loop's unit delay pulls the pitch a little flat.
+            for &(quality, tolerance) in [(0.0, 0.15), (0.5, 0.03), (1.0, 0.03)].iter() {
+                let mut vcf = self_oscillating(1000.0, quality, sample_rate);
+                let freq = oscillation_freq(&mut vcf, sample_rate);
+                assert!(
+                    (freq / 1000.0).log2().abs() < tolerance,
+                    "oscillates at {}Hz at {}Hz sample rate, quality {}",
+                    freq,
+                    sample_rate,
+                    quality
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn self_oscillation_follows_keyboard() {
+        for &sample_rate in SAMPLE_RATES.iter() {
+            let mut low = self_oscillating(500.0, 0.5, sample_rate);
+            let mut high = self_oscillating(500.0, 0.5, sample_rate);
+            high.mod_fc(0.5, KBD_CENTER_FREQ * 2.0);
+            let ratio =
+                oscillation_freq(&mut high, sample_rate) / oscillation_freq(&mut low, sample_rate);
+            assert!(
+                (ratio.log2() - 1.0).abs() < 0.05,
+                "an octave up on the keyboard moves the pitch by {} at {}Hz",
+                ratio,
+                sample_rate
+            );
+        }
+    }
+
+    // Runs a unit sine at the cutoff, where resonance peaks, and returns the
+    // largest output after checking every sample is finite.
+    fn peak_at_cutoff(model: f32, svf_mode: f32, sample_rate: f32) -> f32 {
+        let fc = 1000.0;
+        let param = VCFParam::default();
+        param.cutoff.set(cutoff_for(fc));
+        param.k.set(1.0);
+        param.model.set(model);
+        param.svf_mode.set(svf_mode);
+        let mut vcf = VCF::new(Arc::new(param), sample_rate);
+        vcf.mod_fc(0.5, KBD_CENTER_FREQ);
+
+        let mut peak = 0.0f32;
+        for i in 0..(sample_rate * 2.0) as usize {
+            let x = (2.0 * PI * fc * i as f32 / sample_rate).sin();
+            let y = vcf.filter(x);
+            assert!(y.is_finite(), "output is not finite at sample {}", i);
+            peak = peak.max(y.abs());
+        }
+        peak
+    }
+
+    #[test]
+    fn other_models_stay_bounded_at_max_resonance() {
+        // Transistor ladder, OTA 2-pole, OTA 4-pole, then the SVF in each mode.
+        let cases = [
+            (0.25, 0.0),
+            (0.5, 0.0),
+            (0.75, 0.0),
+            (1.0, 0.0),
+            (1.0, 1.0 / 3.0),
+            (1.0, 2.0 / 3.0),
+            (1.0, 1.0),
+        ];
+        for &sample_rate in SAMPLE_RATES.iter() {
+            for &(model, svf_mode) in cases.iter() {
+                let peak = peak_at_cutoff(model, svf_mode, sample_rate);
+                assert!(
+                    peak <= MAX_MODEL_PEAK,
+                    "model {} mode {} peaks at {} at {}Hz",
+                    model,
+                    svf_mode,
+                    peak,
+                    sample_rate
+                );
+            }
+        }
     }
 }
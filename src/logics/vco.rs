@@ -6,19 +6,108 @@ use rand::prelude::*;
 use std::f32::consts::PI;
 use std::sync::Arc;
 
+// Width 0 is a square, full width would leave no pulse at all.
+const MAX_PULSE_WIDTH: f32 = 0.9;
+
+// Per-source gains roughly match the loudness of each source at full level with
+// peaks around one. The sum is not normalised: as on the analog mixer, stacking
+// sources drives the filter harder, and the output clipper catches the overs.
+const SAW_GAIN: f32 = 1.0;
+const TRI_GAIN: f32 = 1.0;
+const PULSE_GAIN: f32 = 0.7;
+const SUB_GAIN: f32 = 0.7;
+const NOISE_GAIN: f32 = 1.0;
+const OSC2_GAIN: f32 = 1.0;
+
+const OSC2_INTERVAL_RANGE: f32 = 24.0; // semitones either way
+const OSC2_DETUNE_RANGE: f32 = 50.0; // cents either way
+const CROSS_MOD_RANGE: f32 = 4.0; // octaves either way at full amount
+
+pub enum PWMSource {
+    LFO,
+    MANUAL,
+    ENVELOPE,
+}
+
+impl PWMSource {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::LFO,
+            1 => Self::MANUAL,
+            _ => Self::ENVELOPE,
+        }
+    }
+}
+
+pub enum SubMode {
+    SQUARE_1_OCT,
+    SQUARE_2_OCT,
+    PULSE_2_OCT, // 25% duty
+}
+
+impl SubMode {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::SQUARE_1_OCT,
+            1 => Self::SQUARE_2_OCT,
+            _ => Self::PULSE_2_OCT,
+        }
+    }
+}
+
+pub enum Osc2Waveform {
+    SAW,
+    TRIANGLE,
+    PULSE, // shares the pulse width of the first oscillator
+}
+
+impl Osc2Waveform {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::SAW,
+            1 => Self::TRIANGLE,
+            _ => Self::PULSE,
+        }
+    }
+}
+
+pub enum PhaseReset {
+    FREE,
+    ZERO,
+    RANDOM,
+}
+
+impl PhaseReset {
+    pub fn from_f32(value: f32) -> Self {
+        match (value * 2.0).round() as i32 {
+            0 => Self::FREE,
+            1 => Self::ZERO,
+            _ => Self::RANDOM,
+        }
+    }
+}
+
 pub struct VCO {
     sample_rate: f32,
-    time: i32,
+    phase: f32,
+    sub_counter: u8, // counts main oscillator cycles, like the sub's flip-flop divider
+    osc2_phase: f32,
+    rng: StdRng,
     param: Arc<VCOParam>,
     moded_pw: f32,
+    level_mod: [f32; 5], // saw, tri, squ, sub, noise
 }
 
 impl VCO {
     pub fn new(param: Arc<VCOParam>, sample_rate: f32) -> Self {
         Self {
             sample_rate: sample_rate,
-            time: 0,
+            phase: 0.0,
+            sub_counter: 0,
+            osc2_phase: 0.0,
+            rng: StdRng::from_entropy(),
             moded_pw: param.pulse_width.get(),
+            level_mod: [0.0; 5],
             param: param,
         }
     }
@@ -26,65 +115,227 @@ impl VCO {
     pub fn default() -> Self {
         Self {
             sample_rate: 44100.0,
-            time: 0,
+            phase: 0.0,
+            sub_counter: 0,
+            osc2_phase: 0.0,
+            rng: StdRng::from_entropy(),
             param: Arc::new(VCOParam::default()),
             moded_pw: 0.0,
+            level_mod: [0.0; 5],
         }
     }
 
-    fn get_time(&self, n: i32, theta: f32, freq: f32) -> f32 {
-        n as f32 + theta * self.sample_rate / (2.0 * PI * freq)
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    // Seeds the noise source.
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Restarts at a random phase drawn from the (seedable) noise RNG.
+    pub fn randomize_phase(&mut self) {
+        let phase = self.rng.sample::<f32, Standard>(Standard);
+        self.reset_phase(phase);
+    }
+
+    // Moves both oscillators by `shift` cycles without touching the sub divider.
+    pub fn shift_phase(&mut self, shift: f32) {
+        self.phase += shift;
+        self.phase -= self.phase.floor();
+        self.osc2_phase += shift;
+        self.osc2_phase -= self.osc2_phase.floor();
     }
 
-    fn saw_osc(&self, n: i32, theta: f32, freq: f32) -> f32 {
-        let time = self.get_time(n, theta, freq);
-        2.0 * ((time * freq) / self.sample_rate - (time * freq / self.sample_rate + 0.5).floor())
+    // Restarts both oscillators at `phase` (in cycles) and the sub divider.
+    pub fn reset_phase(&mut self, phase: f32) {
+        self.phase = phase - phase.floor();
+        self.osc2_phase = self.phase;
+        self.sub_counter = 0;
     }
 
-    fn tri_osc(&self, n: i32, theta: f32, freq: f32) -> f32 {
-        let time = self.get_time(n, theta - PI / 2.0, freq);
-        1.0 - 2.0 / PI * ((2.0 * PI * freq / self.sample_rate * time).cos()).acos()
+    fn saw_osc(&self, phase: f32) -> f32 {
+        2.0 * (phase - (phase + 0.5).floor())
     }
 
-    fn squ_osc(&self, n: i32, theta: f32, freq: f32) -> f32 {
-        let time = self.get_time(n, theta, freq);
-        let condition = (2.0 * PI * freq / self.sample_rate * time).sin();
-        if condition > 0.0 {
+    fn tri_osc(&self, phase: f32) -> f32 {
+        1.0 - 2.0 / PI * ((2.0 * PI * (phase - 0.25)).cos()).acos()
+    }
+
+    // Width 0 gives a 50% duty cycle, MAX_PULSE_WIDTH narrows it to 5%.
+    fn pulse_osc(&self, phase: f32, width: f32) -> f32 {
+        let duty = 0.5 * (1.0 - width);
+        if phase < duty {
             1.0
-        } else if condition == 0.0 {
-            0.0
         } else {
             -1.0
         }
     }
 
-    fn mixer(&self, n: i32, freq: f32) -> f32 {
-        let saw_out = (self.saw_osc(n, 0.0, freq) + self.saw_osc(n, PI * self.moded_pw, freq))
-            / 2.0
-            * self.param.saw_rate.get();
-        let tri_out = (self.tri_osc(n, 0.0, freq) + self.tri_osc(n, PI * self.moded_pw, freq))
-            / 2.0
-            * self.param.tri_rate.get();
-        let squ_out = (self.squ_osc(n, 0.0, freq) + self.squ_osc(n, PI * self.moded_pw, freq))
-            / 2.0
-            * self.param.squ_rate.get();
-        let sub_out = (self.squ_osc(n, 0.0, freq * 0.5)
-            + self.squ_osc(n, PI * self.moded_pw, freq * 0.5))
-            / 2.0
-            * self.param.sub_rate.get();
-        let noise_out: f32 = (StdRng::from_entropy().sample::<f32, Standard>(Standard) * 2.0 - 1.0)
-            * self.param.noise_rate.get();
-        (tri_out + saw_out + squ_out + sub_out + noise_out) / 5.0
+    // Derived from the cycle counter so it stays phase-locked to the main oscillator.
+    fn sub_osc(&self) -> f32 {
+        match SubMode::from_f32(self.param.sub_mode.get()) {
+            SubMode::SQUARE_1_OCT => {
+                if self.sub_counter & 1 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            SubMode::SQUARE_2_OCT => {
+                if self.sub_counter & 2 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            // Centred so the 25% pulse carries no DC offset.
+            SubMode::PULSE_2_OCT => {
+                if self.sub_counter & 3 == 0 {
+                    1.5
+                } else {
+                    -0.5
+                }
+            }
+        }
     }
 
-    pub fn mod_pw(&mut self, cv: f32) {
-        self.moded_pw = self.param.pulse_width.get()
-            + (1.0 - self.param.pulse_width.get()) * cv * self.param.pulse_width_mod.get();
+    fn osc2(&self) -> f32 {
+        let phase = self.osc2_phase;
+        match Osc2Waveform::from_f32(self.param.osc2_waveform.get()) {
+            Osc2Waveform::SAW => self.saw_osc(phase),
+            Osc2Waveform::TRIANGLE => self.tri_osc(phase),
+            Osc2Waveform::PULSE => self.pulse_osc(phase, self.moded_pw),
+        }
+    }
+
+    // Frequency ratio of the second oscillator from its interval and detune.
+    fn osc2_ratio(&self) -> f32 {
+        let interval = ((self.param.osc2_interval.get() * 2.0 - 1.0) * OSC2_INTERVAL_RANGE).round();
+        let detune = (self.param.osc2_detune.get() * 2.0 - 1.0) * OSC2_DETUNE_RANGE / 100.0;
+        ((interval + detune) / 12.0).exp2()
+    }
+
+    fn level(&self, value: f32, index: usize) -> f32 {
+        (value + self.level_mod[index]).max(0.0).min(1.0)
+    }
+
+    fn mixer(&mut self) -> f32 {
+        let saw_out =
+            self.saw_osc(self.phase) * SAW_GAIN * self.level(self.param.saw_rate.get(), 0);
+        let tri_out =
+            self.tri_osc(self.phase) * TRI_GAIN * self.level(self.param.tri_rate.get(), 1);
+        let squ_out = self.pulse_osc(self.phase, self.moded_pw)
+            * PULSE_GAIN
+            * self.level(self.param.squ_rate.get(), 2);
+        let sub_out = self.sub_osc() * SUB_GAIN * self.level(self.param.sub_rate.get(), 3);
+        let noise_out: f32 = (self.rng.sample::<f32, Standard>(Standard) * 2.0 - 1.0)
+            * NOISE_GAIN
+            * self.level(self.param.noise_rate.get(), 4);
+        let osc2_out = self.osc2() * OSC2_GAIN * self.param.osc2_level.get();
+        tri_out + saw_out + squ_out + sub_out + noise_out + osc2_out
+    }
+
+    // `env` is unipolar and `lfo` bipolar; the selected source swings the width
+    // from the manual setting towards the widest pulse, or back towards square.
+    pub fn mod_pw(&mut self, env: f32, lfo: f32, offset: f32) {
+        let pulse_width = self.param.pulse_width.get().min(MAX_PULSE_WIDTH);
+        let cv = match PWMSource::from_f32(self.param.pwm_source.get()) {
+            PWMSource::LFO => lfo,
+            PWMSource::MANUAL => 0.0,
+            PWMSource::ENVELOPE => env,
+        };
+        let span = if cv >= 0.0 {
+            MAX_PULSE_WIDTH - pulse_width
+        } else {
+            pulse_width
+        };
+        self.moded_pw = (pulse_width + span * cv * self.param.pulse_width_mod.get() + offset)
+            .max(0.0)
+            .min(MAX_PULSE_WIDTH);
+    }
+
+    // Offsets added to the saw, triangle, square, sub and noise mixer levels.
+    pub fn mod_levels(&mut self, saw: f32, tri: f32, squ: f32, sub: f32, noise: f32) {
+        self.level_mod = [saw, tri, squ, sub, noise];
     }
 
     pub fn tick(&mut self, freq: f32) -> f32 {
         let ranged_freq = freq; // * self.param.range.get();
-        self.time += 1;
-        self.mixer(self.time, ranged_freq)
+
+        // The second oscillator is skipped entirely while it is mixed out.
+        if self.param.osc2_level.get() > 0.0 {
+            // Cross mod sweeps the second oscillator exponentially with the
+            // first one's triangle, as the CV would on an analog oscillator.
+            let cross_mod = self.param.osc2_cross_mod.get() * CROSS_MOD_RANGE;
+            let osc2_freq =
+                ranged_freq * self.osc2_ratio() * (cross_mod * self.tri_osc(self.phase)).exp2();
+            let increment = ranged_freq / self.sample_rate;
+            let osc2_increment = osc2_freq / self.sample_rate;
+
+            self.osc2_phase += osc2_increment;
+            let wrapped = self.phase + increment >= 1.0;
+            if wrapped && self.param.osc2_sync.get() >= 0.5 {
+                // Restart at the position the first oscillator's reset fell
+                // within this sample, so the sync pitch stays exact.
+                let overshoot = (self.phase + increment - 1.0) / increment;
+                self.osc2_phase = overshoot * osc2_increment;
+            }
+            self.osc2_phase -= self.osc2_phase.floor();
+        }
+
+        self.phase += ranged_freq / self.sample_rate;
+        if self.phase >= 1.0 {
+            self.phase -= self.phase.floor();
+            self.sub_counter = self.sub_counter.wrapping_add(1);
+        }
+        self.mixer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 256;
+
+    fn vco() -> VCO {
+        let param = VCOParam::default();
+        param.squ_rate.set(1.0);
+        param.sub_rate.set(1.0);
+        param.osc2_level.set(1.0);
+        param.osc2_interval.set(0.75);
+        VCO::new(Arc::new(param), 44100.0)
+    }
+
+    fn render(vco: &mut VCO, samples: usize) -> Vec<f32> {
+        (0..samples).map(|_| vco.tick(220.0)).collect()
+    }
+
+    #[test]
+    fn reset_phase_makes_the_first_samples_deterministic() {
+        let mut first = vco();
+        let mut second = vco();
+        render(&mut first, 500);
+        render(&mut second, 1234);
+
+        first.reset_phase(0.0);
+        second.reset_phase(0.0);
+        assert_eq!(render(&mut first, SAMPLES), render(&mut second, SAMPLES));
+    }
+
+    #[test]
+    fn random_phase_follows_the_seed() {
+        let mut first = vco();
+        let mut second = vco();
+        first.seed(42);
+        second.seed(42);
+        render(&mut first, 500);
+        render(&mut second, 500);
+
+        first.randomize_phase();
+        second.randomize_phase();
+        assert_eq!(render(&mut first, SAMPLES), render(&mut second, SAMPLES));
     }
 }
@@ -0,0 +1,22 @@
+use vst::util::AtomicFloat;
+
+pub struct AmplifierParam {
+    pub mode: AtomicFloat,
+    pub level: AtomicFloat,
+}
+
+impl AmplifierParam {
+    pub fn new(mode: f32, level: f32) -> Self {
+        Self {
+            mode: AtomicFloat::new(mode),
+            level: AtomicFloat::new(level),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            mode: AtomicFloat::new(0.0), // envelope
+            level: AtomicFloat::new(1.0),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use vst::util::AtomicFloat;
+
+pub struct AnalogParam {
+    pub amount: AtomicFloat,
+}
+
+impl AnalogParam {
+    pub fn new(amount: f32) -> Self {
+        Self {
+            amount: AtomicFloat::new(amount),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            amount: AtomicFloat::new(0.0), // fully deterministic
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use vst::util::AtomicFloat;
+
+pub struct DriveStageParam {
+    pub amount: AtomicFloat,
+    pub shape: AtomicFloat,
+}
+
+impl DriveStageParam {
+    pub fn new(amount: f32, shape: f32) -> Self {
+        Self {
+            amount: AtomicFloat::new(amount),
+            shape: AtomicFloat::new(shape),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            amount: AtomicFloat::new(0.0), // off
+            shape: AtomicFloat::new(0.0),  // tube
+        }
+    }
+}
+
+pub struct DriveParam {
+    pub pre: DriveStageParam,
+    pub post: DriveStageParam,
+}
+
+impl DriveParam {
+    pub fn new(pre: DriveStageParam, post: DriveStageParam) -> Self {
+        Self {
+            pre: pre,
+            post: post,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            pre: DriveStageParam::default(),
+            post: DriveStageParam::default(),
+        }
+    }
+}
+
+pub struct BitCrusherParam {
+    pub bypass: AtomicFloat,
+    pub bits: AtomicFloat,
+    pub rate: AtomicFloat,
+}
+
+impl BitCrusherParam {
+    pub fn new(bypass: f32, bits: f32, rate: f32) -> Self {
+        Self {
+            bypass: AtomicFloat::new(bypass),
+            bits: AtomicFloat::new(bits),
+            rate: AtomicFloat::new(rate),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            bypass: AtomicFloat::new(1.0),
+            bits: AtomicFloat::new(0.533), // 8 bits
+            rate: AtomicFloat::new(0.5),   // 1/8 of the sample rate
+        }
+    }
+}
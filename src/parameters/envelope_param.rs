@@ -5,15 +5,32 @@ pub struct EnvelopeParam {
     pub decay: AtomicFloat,
     pub sustain: AtomicFloat,
     pub release: AtomicFloat,
+    pub attack_curve: AtomicFloat,
+    pub decay_curve: AtomicFloat,
+    pub release_curve: AtomicFloat,
+    pub mode: AtomicFloat,
 }
 
 impl EnvelopeParam {
-    pub fn new(attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+    pub fn new(
+        attack: f32,
+        decay: f32,
+        sustain: f32,
+        release: f32,
+        attack_curve: f32,
+        decay_curve: f32,
+        release_curve: f32,
+        mode: f32,
+    ) -> Self {
         Self {
             attack: AtomicFloat::new(attack),
             decay: AtomicFloat::new(decay),
             sustain: AtomicFloat::new(sustain),
             release: AtomicFloat::new(release),
+            attack_curve: AtomicFloat::new(attack_curve),
+            decay_curve: AtomicFloat::new(decay_curve),
+            release_curve: AtomicFloat::new(release_curve),
+            mode: AtomicFloat::new(mode),
         }
     }
 
@@ -23,6 +40,10 @@ impl EnvelopeParam {
             decay: AtomicFloat::new(0.1),
             sustain: AtomicFloat::new(0.5),
             release: AtomicFloat::new(0.3),
+            attack_curve: AtomicFloat::new(0.45), // aims at ~1.5, like a charging capacitor
+            decay_curve: AtomicFloat::new(0.8),
+            release_curve: AtomicFloat::new(0.8),
+            mode: AtomicFloat::new(0.0), // adsr
         }
     }
 }
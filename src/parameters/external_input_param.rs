@@ -0,0 +1,25 @@
+use vst::util::AtomicFloat;
+
+pub struct ExternalInputParam {
+    pub level: AtomicFloat,
+    pub trigger: AtomicFloat,
+    pub threshold: AtomicFloat,
+}
+
+impl ExternalInputParam {
+    pub fn new(level: f32, trigger: f32, threshold: f32) -> Self {
+        Self {
+            level: AtomicFloat::new(level),
+            trigger: AtomicFloat::new(trigger),
+            threshold: AtomicFloat::new(threshold),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            level: AtomicFloat::new(0.0),     // muted
+            trigger: AtomicFloat::new(0.0),   // off
+            threshold: AtomicFloat::new(0.5), // -30 dB
+        }
+    }
+}
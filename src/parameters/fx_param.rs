@@ -0,0 +1,109 @@
+use vst::util::AtomicFloat;
+
+pub struct ChorusParam {
+    pub bypass: AtomicFloat,
+    pub rate: AtomicFloat,
+    pub depth: AtomicFloat,
+    pub mix: AtomicFloat,
+}
+
+impl ChorusParam {
+    pub fn new(bypass: f32, rate: f32, depth: f32, mix: f32) -> Self {
+        Self {
+            bypass: AtomicFloat::new(bypass),
+            rate: AtomicFloat::new(rate),
+            depth: AtomicFloat::new(depth),
+            mix: AtomicFloat::new(mix),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            bypass: AtomicFloat::new(1.0),
+            rate: AtomicFloat::new(0.3), // ~0.5Hz
+            depth: AtomicFloat::new(0.5),
+            mix: AtomicFloat::new(0.5),
+        }
+    }
+}
+
+pub struct DelayParam {
+    pub bypass: AtomicFloat,
+    pub time: AtomicFloat,
+    pub feedback: AtomicFloat,
+    pub tone: AtomicFloat,
+    pub mix: AtomicFloat,
+}
+
+impl DelayParam {
+    pub fn new(bypass: f32, time: f32, feedback: f32, tone: f32, mix: f32) -> Self {
+        Self {
+            bypass: AtomicFloat::new(bypass),
+            time: AtomicFloat::new(time),
+            feedback: AtomicFloat::new(feedback),
+            tone: AtomicFloat::new(tone),
+            mix: AtomicFloat::new(mix),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            bypass: AtomicFloat::new(1.0),
+            time: AtomicFloat::new(0.5), // 1/8 dotted
+            feedback: AtomicFloat::new(0.4),
+            tone: AtomicFloat::new(0.5),
+            mix: AtomicFloat::new(0.3),
+        }
+    }
+}
+
+pub struct ReverbParam {
+    pub bypass: AtomicFloat,
+    pub size: AtomicFloat,
+    pub damping: AtomicFloat,
+    pub mix: AtomicFloat,
+}
+
+impl ReverbParam {
+    pub fn new(bypass: f32, size: f32, damping: f32, mix: f32) -> Self {
+        Self {
+            bypass: AtomicFloat::new(bypass),
+            size: AtomicFloat::new(size),
+            damping: AtomicFloat::new(damping),
+            mix: AtomicFloat::new(mix),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            bypass: AtomicFloat::new(1.0),
+            size: AtomicFloat::new(0.5),
+            damping: AtomicFloat::new(0.5),
+            mix: AtomicFloat::new(0.2),
+        }
+    }
+}
+
+pub struct FXParam {
+    pub chorus: ChorusParam,
+    pub delay: DelayParam,
+    pub reverb: ReverbParam,
+}
+
+impl FXParam {
+    pub fn new(chorus: ChorusParam, delay: DelayParam, reverb: ReverbParam) -> Self {
+        Self {
+            chorus: chorus,
+            delay: delay,
+            reverb: reverb,
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            chorus: ChorusParam::default(),
+            delay: DelayParam::default(),
+            reverb: ReverbParam::default(),
+        }
+    }
+}
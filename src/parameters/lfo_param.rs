@@ -0,0 +1,22 @@
+use vst::util::AtomicFloat;
+
+pub struct LFOParam {
+    pub rate: AtomicFloat,
+    pub waveform: AtomicFloat,
+}
+
+impl LFOParam {
+    pub fn new(rate: f32, waveform: f32) -> Self {
+        Self {
+            rate: AtomicFloat::new(rate),
+            waveform: AtomicFloat::new(waveform),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            rate: AtomicFloat::new(0.5),     // ~1.7Hz
+            waveform: AtomicFloat::new(0.0), // triangle
+        }
+    }
+}
@@ -1,4 +1,16 @@
+pub mod amplifier_param;
+pub mod analog_param;
+pub mod drive_param;
 pub mod envelope_param;
+pub mod external_input_param;
+pub mod fx_param;
+pub mod lfo_param;
+pub mod mod_matrix_param;
+pub mod output_param;
+pub mod oversampling_param;
+pub mod performance_param;
 pub mod sh101_param;
+pub mod tuning_param;
+pub mod unison_param;
 pub mod vcf_param;
 pub mod vco_param;
@@ -0,0 +1,43 @@
+use vst::util::AtomicFloat;
+
+pub const MOD_SLOTS: usize = 8;
+
+pub struct ModSlotParam {
+    pub source: AtomicFloat,
+    pub destination: AtomicFloat,
+    pub amount: AtomicFloat,
+}
+
+impl ModSlotParam {
+    pub fn new(source: f32, destination: f32, amount: f32) -> Self {
+        Self {
+            source: AtomicFloat::new(source),
+            destination: AtomicFloat::new(destination),
+            amount: AtomicFloat::new(amount),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            source: AtomicFloat::new(0.0),      // none
+            destination: AtomicFloat::new(0.0), // none
+            amount: AtomicFloat::new(0.5),      // bipolar, 0.5 = no modulation
+        }
+    }
+}
+
+pub struct ModMatrixParam {
+    pub slots: Vec<ModSlotParam>,
+}
+
+impl ModMatrixParam {
+    pub fn new(slots: Vec<ModSlotParam>) -> Self {
+        Self { slots: slots }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            slots: (0..MOD_SLOTS).map(|_| ModSlotParam::default()).collect(),
+        }
+    }
+}
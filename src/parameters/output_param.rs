@@ -0,0 +1,28 @@
+use vst::util::AtomicFloat;
+
+pub struct OutputParam {
+    pub volume: AtomicFloat,
+    pub pan: AtomicFloat,
+    pub width: AtomicFloat,
+    pub mono: AtomicFloat,
+}
+
+impl OutputParam {
+    pub fn new(volume: f32, pan: f32, width: f32, mono: f32) -> Self {
+        Self {
+            volume: AtomicFloat::new(volume),
+            pan: AtomicFloat::new(pan),
+            width: AtomicFloat::new(width),
+            mono: AtomicFloat::new(mono),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            volume: AtomicFloat::new(0.9), // 0 dB
+            pan: AtomicFloat::new(0.5),    // centre
+            width: AtomicFloat::new(0.5),  // unchanged
+            mono: AtomicFloat::new(0.0),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use vst::util::AtomicFloat;
+
+pub struct OversamplingParam {
+    pub realtime: AtomicFloat,
+    pub offline: AtomicFloat,
+}
+
+impl OversamplingParam {
+    pub fn new(realtime: f32, offline: f32) -> Self {
+        Self {
+            realtime: AtomicFloat::new(realtime),
+            offline: AtomicFloat::new(offline),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            realtime: AtomicFloat::new(0.0),      // 1x
+            offline: AtomicFloat::new(2.0 / 3.0), // 4x
+        }
+    }
+}
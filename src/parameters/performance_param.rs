@@ -0,0 +1,25 @@
+use vst::util::AtomicFloat;
+
+pub struct PerformanceParam {
+    pub accent_amount: AtomicFloat,
+    pub accent_threshold: AtomicFloat,
+    pub slide_time: AtomicFloat,
+}
+
+impl PerformanceParam {
+    pub fn new(accent_amount: f32, accent_threshold: f32, slide_time: f32) -> Self {
+        Self {
+            accent_amount: AtomicFloat::new(accent_amount),
+            accent_threshold: AtomicFloat::new(accent_threshold),
+            slide_time: AtomicFloat::new(slide_time),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            accent_amount: AtomicFloat::new(0.5),
+            accent_threshold: AtomicFloat::new(0.8), // velocity 102 and up
+            slide_time: AtomicFloat::new(0.06),
+        }
+    }
+}
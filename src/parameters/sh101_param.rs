@@ -1,12 +1,27 @@
 use crate::parameters;
 
+use parameters::amplifier_param::AmplifierParam;
+use parameters::analog_param::AnalogParam;
+use parameters::drive_param::{BitCrusherParam, DriveParam};
 use parameters::envelope_param::EnvelopeParam;
+use parameters::external_input_param::ExternalInputParam;
+use parameters::fx_param::FXParam;
+use parameters::lfo_param::LFOParam;
+use parameters::mod_matrix_param::{ModMatrixParam, MOD_SLOTS};
+use parameters::output_param::OutputParam;
+use parameters::oversampling_param::OversamplingParam;
+use parameters::performance_param::PerformanceParam;
+use parameters::tuning_param::TuningParam;
+use parameters::unison_param::UnisonParam;
 use parameters::vcf_param::VCFParam;
 use parameters::vco_param::VCOParam;
 use vst::plugin::PluginParameters;
 
 use std::sync::Arc;
 
+// One past the highest index handled by Params::from_i32.
+const NUM_PARAMETERS: i32 = 106;
+
 enum Params {
     VCO_RANGE,
     VCO_PULSE_WIDTH,
@@ -32,6 +47,91 @@ enum Params {
     MOD_SUSTAIN,
     MOD_RELEASE,
 
+    VCF_COMPENSATION,
+    VCF_QUALITY,
+
+    OVERSAMPLING_REALTIME,
+    OVERSAMPLING_OFFLINE,
+
+    VCF_MODEL,
+    VCF_SVF_MODE,
+
+    VCA_ATTACK_CURVE,
+    VCA_DECAY_CURVE,
+    VCA_RELEASE_CURVE,
+    MOD_ATTACK_CURVE,
+    MOD_DECAY_CURVE,
+    MOD_RELEASE_CURVE,
+
+    VCA_MODE,
+    MOD_MODE,
+
+    AMP_MODE,
+    AMP_LEVEL,
+
+    ACCENT_AMOUNT,
+    ACCENT_THRESHOLD,
+    SLIDE_TIME,
+
+    LFO_RATE,
+    LFO_WAVEFORM,
+
+    MOD_SLOT_SOURCE(usize),
+    MOD_SLOT_DESTINATION(usize),
+    MOD_SLOT_AMOUNT(usize),
+
+    VCO_PWM_SOURCE,
+    VCO_SUB_MODE,
+
+    MASTER_VOLUME,
+
+    VCO2_LEVEL,
+    VCO2_INTERVAL,
+    VCO2_DETUNE,
+    VCO2_WAVEFORM,
+    VCO2_SYNC,
+    VCO2_CROSS_MOD,
+
+    UNISON_VOICES,
+    UNISON_DETUNE,
+    UNISON_SPREAD,
+
+    OUTPUT_PAN,
+    OUTPUT_WIDTH,
+    OUTPUT_MONO,
+
+    EXT_LEVEL,
+    EXT_TRIGGER,
+    EXT_THRESHOLD,
+
+    CHORUS_BYPASS,
+    CHORUS_RATE,
+    CHORUS_DEPTH,
+    CHORUS_MIX,
+    DELAY_BYPASS,
+    DELAY_TIME,
+    DELAY_FEEDBACK,
+    DELAY_TONE,
+    DELAY_MIX,
+    REVERB_BYPASS,
+    REVERB_SIZE,
+    REVERB_DAMPING,
+    REVERB_MIX,
+
+    PRE_DRIVE_AMOUNT,
+    PRE_DRIVE_SHAPE,
+    POST_DRIVE_AMOUNT,
+    POST_DRIVE_SHAPE,
+    BIT_CRUSHER_BYPASS,
+    BIT_CRUSHER_BITS,
+    BIT_CRUSHER_RATE,
+
+    MASTER_TUNE,
+
+    ANALOG_AMOUNT,
+
+    VCO_PHASE_RESET,
+
     UNKNOWN,
 }
 
@@ -62,6 +162,96 @@ impl Params {
             18 => Self::MOD_SUSTAIN,
             19 => Self::MOD_RELEASE,
 
+            20 => Self::VCF_COMPENSATION,
+            21 => Self::VCF_QUALITY,
+
+            22 => Self::OVERSAMPLING_REALTIME,
+            23 => Self::OVERSAMPLING_OFFLINE,
+
+            24 => Self::VCF_MODEL,
+            25 => Self::VCF_SVF_MODE,
+
+            26 => Self::VCA_ATTACK_CURVE,
+            27 => Self::VCA_DECAY_CURVE,
+            28 => Self::VCA_RELEASE_CURVE,
+            29 => Self::MOD_ATTACK_CURVE,
+            30 => Self::MOD_DECAY_CURVE,
+            31 => Self::MOD_RELEASE_CURVE,
+
+            32 => Self::VCA_MODE,
+            33 => Self::MOD_MODE,
+
+            34 => Self::AMP_MODE,
+            35 => Self::AMP_LEVEL,
+
+            36 => Self::ACCENT_AMOUNT,
+            37 => Self::ACCENT_THRESHOLD,
+            38 => Self::SLIDE_TIME,
+
+            39 => Self::LFO_RATE,
+            40 => Self::LFO_WAVEFORM,
+
+            i if i >= 41 && i < 41 + 3 * MOD_SLOTS as i32 => {
+                let slot = (index - 41) as usize / 3;
+                match (index - 41) % 3 {
+                    0 => Self::MOD_SLOT_SOURCE(slot),
+                    1 => Self::MOD_SLOT_DESTINATION(slot),
+                    _ => Self::MOD_SLOT_AMOUNT(slot),
+                }
+            }
+
+            65 => Self::VCO_PWM_SOURCE,
+            66 => Self::VCO_SUB_MODE,
+
+            67 => Self::MASTER_VOLUME,
+
+            68 => Self::VCO2_LEVEL,
+            69 => Self::VCO2_INTERVAL,
+            70 => Self::VCO2_DETUNE,
+            71 => Self::VCO2_WAVEFORM,
+            72 => Self::VCO2_SYNC,
+            73 => Self::VCO2_CROSS_MOD,
+
+            74 => Self::UNISON_VOICES,
+            75 => Self::UNISON_DETUNE,
+            76 => Self::UNISON_SPREAD,
+
+            77 => Self::OUTPUT_PAN,
+            78 => Self::OUTPUT_WIDTH,
+            79 => Self::OUTPUT_MONO,
+
+            80 => Self::EXT_LEVEL,
+            81 => Self::EXT_TRIGGER,
+            82 => Self::EXT_THRESHOLD,
+
+            83 => Self::CHORUS_BYPASS,
+            84 => Self::CHORUS_RATE,
+            85 => Self::CHORUS_DEPTH,
+            86 => Self::CHORUS_MIX,
+            87 => Self::DELAY_BYPASS,
+            88 => Self::DELAY_TIME,
+            89 => Self::DELAY_FEEDBACK,
+            90 => Self::DELAY_TONE,
+            91 => Self::DELAY_MIX,
+            92 => Self::REVERB_BYPASS,
+            93 => Self::REVERB_SIZE,
+            94 => Self::REVERB_DAMPING,
+            95 => Self::REVERB_MIX,
+
+            96 => Self::PRE_DRIVE_AMOUNT,
+            97 => Self::PRE_DRIVE_SHAPE,
+            98 => Self::POST_DRIVE_AMOUNT,
+            99 => Self::POST_DRIVE_SHAPE,
+            100 => Self::BIT_CRUSHER_BYPASS,
+            101 => Self::BIT_CRUSHER_BITS,
+            102 => Self::BIT_CRUSHER_RATE,
+
+            103 => Self::MASTER_TUNE,
+
+            104 => Self::ANALOG_AMOUNT,
+
+            105 => Self::VCO_PHASE_RESET,
+
             _ => Self::UNKNOWN,
         }
     }
@@ -73,6 +263,19 @@ pub struct SH101Param {
     pub vca_param: Arc<EnvelopeParam>,
     pub vcf_param: Arc<VCFParam>,
     pub mod_param: Arc<EnvelopeParam>,
+    pub oversampling_param: Arc<OversamplingParam>,
+    pub amp_param: Arc<AmplifierParam>,
+    pub performance_param: Arc<PerformanceParam>,
+    pub lfo_param: Arc<LFOParam>,
+    pub mod_matrix_param: Arc<ModMatrixParam>,
+    pub output_param: Arc<OutputParam>,
+    pub unison_param: Arc<UnisonParam>,
+    pub external_input_param: Arc<ExternalInputParam>,
+    pub fx_param: Arc<FXParam>,
+    pub drive_param: Arc<DriveParam>,
+    pub bit_crusher_param: Arc<BitCrusherParam>,
+    pub tuning_param: Arc<TuningParam>,
+    pub analog_param: Arc<AnalogParam>,
 }
 
 impl SH101Param {
@@ -81,13 +284,39 @@ impl SH101Param {
         vcf_param: Arc<VCFParam>,
         vca_param: Arc<EnvelopeParam>,
         mod_param: Arc<EnvelopeParam>,
+        oversampling_param: Arc<OversamplingParam>,
+        amp_param: Arc<AmplifierParam>,
+        performance_param: Arc<PerformanceParam>,
+        lfo_param: Arc<LFOParam>,
+        mod_matrix_param: Arc<ModMatrixParam>,
+        output_param: Arc<OutputParam>,
+        unison_param: Arc<UnisonParam>,
+        external_input_param: Arc<ExternalInputParam>,
+        fx_param: Arc<FXParam>,
+        drive_param: Arc<DriveParam>,
+        bit_crusher_param: Arc<BitCrusherParam>,
+        tuning_param: Arc<TuningParam>,
+        analog_param: Arc<AnalogParam>,
     ) -> Self {
         Self {
-            num_parameters: 8 + 4 + 4 + 4,
+            num_parameters: NUM_PARAMETERS,
             vco_param: vco_param,
             vca_param: vca_param,
             vcf_param: vcf_param,
             mod_param: mod_param,
+            oversampling_param: oversampling_param,
+            amp_param: amp_param,
+            performance_param: performance_param,
+            lfo_param: lfo_param,
+            mod_matrix_param: mod_matrix_param,
+            output_param: output_param,
+            unison_param: unison_param,
+            external_input_param: external_input_param,
+            fx_param: fx_param,
+            drive_param: drive_param,
+            bit_crusher_param: bit_crusher_param,
+            tuning_param: tuning_param,
+            analog_param: analog_param,
         }
     }
 }
@@ -120,6 +349,90 @@ impl PluginParameters for SH101Param {
             Params::MOD_SUSTAIN => "[-]".to_string(),
             Params::MOD_RELEASE => "[-]".to_string(),
 
+            Params::VCF_COMPENSATION => "[-]".to_string(),
+            Params::VCF_QUALITY => "[-]".to_string(),
+            Params::VCF_MODEL => "[-]".to_string(),
+            Params::VCF_SVF_MODE => "[-]".to_string(),
+
+            Params::VCA_ATTACK_CURVE => "[-]".to_string(),
+            Params::VCA_DECAY_CURVE => "[-]".to_string(),
+            Params::VCA_RELEASE_CURVE => "[-]".to_string(),
+            Params::MOD_ATTACK_CURVE => "[-]".to_string(),
+            Params::MOD_DECAY_CURVE => "[-]".to_string(),
+            Params::MOD_RELEASE_CURVE => "[-]".to_string(),
+
+            Params::VCA_MODE => "[-]".to_string(),
+            Params::MOD_MODE => "[-]".to_string(),
+
+            Params::AMP_MODE => "[-]".to_string(),
+            Params::AMP_LEVEL => "[-]".to_string(),
+
+            Params::ACCENT_AMOUNT => "[-]".to_string(),
+            Params::ACCENT_THRESHOLD => "[-]".to_string(),
+            Params::SLIDE_TIME => "[-]".to_string(),
+
+            Params::LFO_RATE => "[-]".to_string(),
+            Params::LFO_WAVEFORM => "[-]".to_string(),
+
+            Params::MOD_SLOT_SOURCE(_) => "[-]".to_string(),
+            Params::MOD_SLOT_DESTINATION(_) => "[-]".to_string(),
+            Params::MOD_SLOT_AMOUNT(_) => "[-]".to_string(),
+
+            Params::VCO_PWM_SOURCE => "[-]".to_string(),
+            Params::VCO_SUB_MODE => "[-]".to_string(),
+
+            Params::MASTER_VOLUME => "[-]".to_string(),
+
+            Params::VCO2_LEVEL => "[-]".to_string(),
+            Params::VCO2_INTERVAL => "[-]".to_string(),
+            Params::VCO2_DETUNE => "[-]".to_string(),
+            Params::VCO2_WAVEFORM => "[-]".to_string(),
+            Params::VCO2_SYNC => "[-]".to_string(),
+            Params::VCO2_CROSS_MOD => "[-]".to_string(),
+
+            Params::UNISON_VOICES => "[-]".to_string(),
+            Params::UNISON_DETUNE => "[-]".to_string(),
+            Params::UNISON_SPREAD => "[-]".to_string(),
+
+            Params::OUTPUT_PAN => "[-]".to_string(),
+            Params::OUTPUT_WIDTH => "[-]".to_string(),
+            Params::OUTPUT_MONO => "[-]".to_string(),
+
+            Params::EXT_LEVEL => "[-]".to_string(),
+            Params::EXT_TRIGGER => "[-]".to_string(),
+            Params::EXT_THRESHOLD => "[-]".to_string(),
+
+            Params::CHORUS_BYPASS => "[-]".to_string(),
+            Params::CHORUS_RATE => "[-]".to_string(),
+            Params::CHORUS_DEPTH => "[-]".to_string(),
+            Params::CHORUS_MIX => "[-]".to_string(),
+            Params::DELAY_BYPASS => "[-]".to_string(),
+            Params::DELAY_TIME => "[-]".to_string(),
+            Params::DELAY_FEEDBACK => "[-]".to_string(),
+            Params::DELAY_TONE => "[-]".to_string(),
+            Params::DELAY_MIX => "[-]".to_string(),
+            Params::REVERB_BYPASS => "[-]".to_string(),
+            Params::REVERB_SIZE => "[-]".to_string(),
+            Params::REVERB_DAMPING => "[-]".to_string(),
+            Params::REVERB_MIX => "[-]".to_string(),
+
+            Params::PRE_DRIVE_AMOUNT => "[-]".to_string(),
+            Params::PRE_DRIVE_SHAPE => "[-]".to_string(),
+            Params::POST_DRIVE_AMOUNT => "[-]".to_string(),
+            Params::POST_DRIVE_SHAPE => "[-]".to_string(),
+            Params::BIT_CRUSHER_BYPASS => "[-]".to_string(),
+            Params::BIT_CRUSHER_BITS => "[-]".to_string(),
+            Params::BIT_CRUSHER_RATE => "[-]".to_string(),
+
+            Params::MASTER_TUNE => "[-]".to_string(),
+
+            Params::ANALOG_AMOUNT => "[-]".to_string(),
+
+            Params::VCO_PHASE_RESET => "[-]".to_string(),
+
+            Params::OVERSAMPLING_REALTIME => "[-]".to_string(),
+            Params::OVERSAMPLING_OFFLINE => "[-]".to_string(),
+
             Params::UNKNOWN => "".to_string(),
         }
     }
@@ -142,7 +455,7 @@ impl PluginParameters for SH101Param {
             Params::VCA_RELEASE => "VCA Release".to_string(),
 
             Params::VCF_CUTOFF => "VCF Cutoff".to_string(),
-            Params::VCF_CUTOFF_MOD => "VCF Cutoff Modulation Rate".to_string(),
+            Params::VCF_CUTOFF_MOD => "VCF Envelope Amount".to_string(),
             Params::VCF_K => "VCF Resonance".to_string(),
             Params::VCF_KBD => "Keyboard Follow".to_string(),
 
@@ -151,6 +464,90 @@ impl PluginParameters for SH101Param {
             Params::MOD_SUSTAIN => "Mod Sustain".to_string(),
             Params::MOD_RELEASE => "Mod Release".to_string(),
 
+            Params::VCF_COMPENSATION => "VCF Gain Compensation".to_string(),
+            Params::VCF_QUALITY => "VCF Quality".to_string(),
+            Params::VCF_MODEL => "VCF Model".to_string(),
+            Params::VCF_SVF_MODE => "VCF SVF Mode".to_string(),
+
+            Params::VCA_ATTACK_CURVE => "VCA Attack Curve".to_string(),
+            Params::VCA_DECAY_CURVE => "VCA Decay Curve".to_string(),
+            Params::VCA_RELEASE_CURVE => "VCA Release Curve".to_string(),
+            Params::MOD_ATTACK_CURVE => "Mod Attack Curve".to_string(),
+            Params::MOD_DECAY_CURVE => "Mod Decay Curve".to_string(),
+            Params::MOD_RELEASE_CURVE => "Mod Release Curve".to_string(),
+
+            Params::VCA_MODE => "VCA Envelope Mode".to_string(),
+            Params::MOD_MODE => "Mod Envelope Mode".to_string(),
+
+            Params::AMP_MODE => "VCA Mode".to_string(),
+            Params::AMP_LEVEL => "VCA Level".to_string(),
+
+            Params::ACCENT_AMOUNT => "Accent Amount".to_string(),
+            Params::ACCENT_THRESHOLD => "Accent Velocity Threshold".to_string(),
+            Params::SLIDE_TIME => "Slide Time".to_string(),
+
+            Params::LFO_RATE => "LFO Rate".to_string(),
+            Params::LFO_WAVEFORM => "LFO Waveform".to_string(),
+
+            Params::MOD_SLOT_SOURCE(slot) => format!("Mod {} Source", slot + 1),
+            Params::MOD_SLOT_DESTINATION(slot) => format!("Mod {} Destination", slot + 1),
+            Params::MOD_SLOT_AMOUNT(slot) => format!("Mod {} Amount", slot + 1),
+
+            Params::VCO_PWM_SOURCE => "VCO PWM Source".to_string(),
+            Params::VCO_SUB_MODE => "VCO Sub Mode".to_string(),
+
+            Params::MASTER_VOLUME => "Master Volume".to_string(),
+
+            Params::VCO2_LEVEL => "VCO2 Level".to_string(),
+            Params::VCO2_INTERVAL => "VCO2 Interval".to_string(),
+            Params::VCO2_DETUNE => "VCO2 Detune".to_string(),
+            Params::VCO2_WAVEFORM => "VCO2 Waveform".to_string(),
+            Params::VCO2_SYNC => "VCO2 Sync".to_string(),
+            Params::VCO2_CROSS_MOD => "VCO2 Cross Mod".to_string(),
+
+            Params::UNISON_VOICES => "Unison Voices".to_string(),
+            Params::UNISON_DETUNE => "Unison Detune".to_string(),
+            Params::UNISON_SPREAD => "Unison Spread".to_string(),
+
+            Params::OUTPUT_PAN => "Pan".to_string(),
+            Params::OUTPUT_WIDTH => "Stereo Width".to_string(),
+            Params::OUTPUT_MONO => "Mono".to_string(),
+
+            Params::EXT_LEVEL => "External Input Level".to_string(),
+            Params::EXT_TRIGGER => "External Input Trigger".to_string(),
+            Params::EXT_THRESHOLD => "External Input Threshold".to_string(),
+
+            Params::CHORUS_BYPASS => "Chorus Bypass".to_string(),
+            Params::CHORUS_RATE => "Chorus Rate".to_string(),
+            Params::CHORUS_DEPTH => "Chorus Depth".to_string(),
+            Params::CHORUS_MIX => "Chorus Mix".to_string(),
+            Params::DELAY_BYPASS => "Delay Bypass".to_string(),
+            Params::DELAY_TIME => "Delay Time".to_string(),
+            Params::DELAY_FEEDBACK => "Delay Feedback".to_string(),
+            Params::DELAY_TONE => "Delay Tone".to_string(),
+            Params::DELAY_MIX => "Delay Mix".to_string(),
+            Params::REVERB_BYPASS => "Reverb Bypass".to_string(),
+            Params::REVERB_SIZE => "Reverb Size".to_string(),
+            Params::REVERB_DAMPING => "Reverb Damping".to_string(),
+            Params::REVERB_MIX => "Reverb Mix".to_string(),
+
+            Params::PRE_DRIVE_AMOUNT => "Pre-Filter Drive".to_string(),
+            Params::PRE_DRIVE_SHAPE => "Pre-Filter Drive Shape".to_string(),
+            Params::POST_DRIVE_AMOUNT => "Post-Filter Drive".to_string(),
+            Params::POST_DRIVE_SHAPE => "Post-Filter Drive Shape".to_string(),
+            Params::BIT_CRUSHER_BYPASS => "Bit Crusher Bypass".to_string(),
+            Params::BIT_CRUSHER_BITS => "Bit Crusher Bits".to_string(),
+            Params::BIT_CRUSHER_RATE => "Bit Crusher Rate".to_string(),
+
+            Params::MASTER_TUNE => "Master Tune".to_string(),
+
+            Params::ANALOG_AMOUNT => "Analog Amount".to_string(),
+
+            Params::VCO_PHASE_RESET => "VCO Phase Reset".to_string(),
+
+            Params::OVERSAMPLING_REALTIME => "Oversampling Realtime".to_string(),
+            Params::OVERSAMPLING_OFFLINE => "Oversampling Offline".to_string(),
+
             Params::UNKNOWN => "".to_string(),
         }
     }
@@ -186,6 +583,92 @@ impl PluginParameters for SH101Param {
             Params::MOD_SUSTAIN => self.mod_param.sustain.set(value),
             Params::MOD_RELEASE => self.mod_param.release.set(value),
 
+            Params::VCF_COMPENSATION => self.vcf_param.compensation.set(value),
+            Params::VCF_QUALITY => self.vcf_param.quality.set(value),
+            Params::VCF_MODEL => self.vcf_param.model.set(value),
+            Params::VCF_SVF_MODE => self.vcf_param.svf_mode.set(value),
+
+            Params::VCA_ATTACK_CURVE => self.vca_param.attack_curve.set(value),
+            Params::VCA_DECAY_CURVE => self.vca_param.decay_curve.set(value),
+            Params::VCA_RELEASE_CURVE => self.vca_param.release_curve.set(value),
+            Params::MOD_ATTACK_CURVE => self.mod_param.attack_curve.set(value),
+            Params::MOD_DECAY_CURVE => self.mod_param.decay_curve.set(value),
+            Params::MOD_RELEASE_CURVE => self.mod_param.release_curve.set(value),
+
+            Params::VCA_MODE => self.vca_param.mode.set(value),
+            Params::MOD_MODE => self.mod_param.mode.set(value),
+
+            Params::AMP_MODE => self.amp_param.mode.set(value),
+            Params::AMP_LEVEL => self.amp_param.level.set(value),
+
+            Params::ACCENT_AMOUNT => self.performance_param.accent_amount.set(value),
+            Params::ACCENT_THRESHOLD => self.performance_param.accent_threshold.set(value),
+            Params::SLIDE_TIME => self.performance_param.slide_time.set(value),
+
+            Params::LFO_RATE => self.lfo_param.rate.set(value),
+            Params::LFO_WAVEFORM => self.lfo_param.waveform.set(value),
+
+            Params::MOD_SLOT_SOURCE(slot) => self.mod_matrix_param.slots[slot].source.set(value),
+            Params::MOD_SLOT_DESTINATION(slot) => {
+                self.mod_matrix_param.slots[slot].destination.set(value)
+            }
+            Params::MOD_SLOT_AMOUNT(slot) => self.mod_matrix_param.slots[slot].amount.set(value),
+
+            Params::VCO_PWM_SOURCE => self.vco_param.pwm_source.set(value),
+            Params::VCO_SUB_MODE => self.vco_param.sub_mode.set(value),
+
+            Params::MASTER_VOLUME => self.output_param.volume.set(value),
+
+            Params::VCO2_LEVEL => self.vco_param.osc2_level.set(value),
+            Params::VCO2_INTERVAL => self.vco_param.osc2_interval.set(value),
+            Params::VCO2_DETUNE => self.vco_param.osc2_detune.set(value),
+            Params::VCO2_WAVEFORM => self.vco_param.osc2_waveform.set(value),
+            Params::VCO2_SYNC => self.vco_param.osc2_sync.set(value),
+            Params::VCO2_CROSS_MOD => self.vco_param.osc2_cross_mod.set(value),
+
+            Params::UNISON_VOICES => self.unison_param.voices.set(value),
+            Params::UNISON_DETUNE => self.unison_param.detune.set(value),
+            Params::UNISON_SPREAD => self.unison_param.spread.set(value),
+
+            Params::OUTPUT_PAN => self.output_param.pan.set(value),
+            Params::OUTPUT_WIDTH => self.output_param.width.set(value),
+            Params::OUTPUT_MONO => self.output_param.mono.set(value),
+
+            Params::EXT_LEVEL => self.external_input_param.level.set(value),
+            Params::EXT_TRIGGER => self.external_input_param.trigger.set(value),
+            Params::EXT_THRESHOLD => self.external_input_param.threshold.set(value),
+
+            Params::CHORUS_BYPASS => self.fx_param.chorus.bypass.set(value),
+            Params::CHORUS_RATE => self.fx_param.chorus.rate.set(value),
+            Params::CHORUS_DEPTH => self.fx_param.chorus.depth.set(value),
+            Params::CHORUS_MIX => self.fx_param.chorus.mix.set(value),
+            Params::DELAY_BYPASS => self.fx_param.delay.bypass.set(value),
+            Params::DELAY_TIME => self.fx_param.delay.time.set(value),
+            Params::DELAY_FEEDBACK => self.fx_param.delay.feedback.set(value),
+            Params::DELAY_TONE => self.fx_param.delay.tone.set(value),
+            Params::DELAY_MIX => self.fx_param.delay.mix.set(value),
+            Params::REVERB_BYPASS => self.fx_param.reverb.bypass.set(value),
+            Params::REVERB_SIZE => self.fx_param.reverb.size.set(value),
+            Params::REVERB_DAMPING => self.fx_param.reverb.damping.set(value),
+            Params::REVERB_MIX => self.fx_param.reverb.mix.set(value),
+
+            Params::PRE_DRIVE_AMOUNT => self.drive_param.pre.amount.set(value),
+            Params::PRE_DRIVE_SHAPE => self.drive_param.pre.shape.set(value),
+            Params::POST_DRIVE_AMOUNT => self.drive_param.post.amount.set(value),
+            Params::POST_DRIVE_SHAPE => self.drive_param.post.shape.set(value),
+            Params::BIT_CRUSHER_BYPASS => self.bit_crusher_param.bypass.set(value),
+            Params::BIT_CRUSHER_BITS => self.bit_crusher_param.bits.set(value),
+            Params::BIT_CRUSHER_RATE => self.bit_crusher_param.rate.set(value),
+
+            Params::MASTER_TUNE => self.tuning_param.master_tune.set(value),
+
+            Params::ANALOG_AMOUNT => self.analog_param.amount.set(value),
+
+            Params::VCO_PHASE_RESET => self.vco_param.phase_reset.set(value),
+
+            Params::OVERSAMPLING_REALTIME => self.oversampling_param.realtime.set(value),
+            Params::OVERSAMPLING_OFFLINE => self.oversampling_param.offline.set(value),
+
             Params::UNKNOWN => (),
         }
     }
@@ -217,6 +700,92 @@ impl PluginParameters for SH101Param {
             Params::MOD_SUSTAIN => self.mod_param.sustain.get(),
             Params::MOD_RELEASE => self.mod_param.release.get(),
 
+            Params::VCF_COMPENSATION => self.vcf_param.compensation.get(),
+            Params::VCF_QUALITY => self.vcf_param.quality.get(),
+            Params::VCF_MODEL => self.vcf_param.model.get(),
+            Params::VCF_SVF_MODE => self.vcf_param.svf_mode.get(),
+
+            Params::VCA_ATTACK_CURVE => self.vca_param.attack_curve.get(),
+            Params::VCA_DECAY_CURVE => self.vca_param.decay_curve.get(),
+            Params::VCA_RELEASE_CURVE => self.vca_param.release_curve.get(),
+            Params::MOD_ATTACK_CURVE => self.mod_param.attack_curve.get(),
+            Params::MOD_DECAY_CURVE => self.mod_param.decay_curve.get(),
+            Params::MOD_RELEASE_CURVE => self.mod_param.release_curve.get(),
+
+            Params::VCA_MODE => self.vca_param.mode.get(),
+            Params::MOD_MODE => self.mod_param.mode.get(),
+
+            Params::AMP_MODE => self.amp_param.mode.get(),
+            Params::AMP_LEVEL => self.amp_param.level.get(),
+
+            Params::ACCENT_AMOUNT => self.performance_param.accent_amount.get(),
+            Params::ACCENT_THRESHOLD => self.performance_param.accent_threshold.get(),
+            Params::SLIDE_TIME => self.performance_param.slide_time.get(),
+
+            Params::LFO_RATE => self.lfo_param.rate.get(),
+            Params::LFO_WAVEFORM => self.lfo_param.waveform.get(),
+
+            Params::MOD_SLOT_SOURCE(slot) => self.mod_matrix_param.slots[slot].source.get(),
+            Params::MOD_SLOT_DESTINATION(slot) => {
+                self.mod_matrix_param.slots[slot].destination.get()
+            }
+            Params::MOD_SLOT_AMOUNT(slot) => self.mod_matrix_param.slots[slot].amount.get(),
+
+            Params::VCO_PWM_SOURCE => self.vco_param.pwm_source.get(),
+            Params::VCO_SUB_MODE => self.vco_param.sub_mode.get(),
+
+            Params::MASTER_VOLUME => self.output_param.volume.get(),
+
+            Params::VCO2_LEVEL => self.vco_param.osc2_level.get(),
+            Params::VCO2_INTERVAL => self.vco_param.osc2_interval.get(),
+            Params::VCO2_DETUNE => self.vco_param.osc2_detune.get(),
+            Params::VCO2_WAVEFORM => self.vco_param.osc2_waveform.get(),
+            Params::VCO2_SYNC => self.vco_param.osc2_sync.get(),
+            Params::VCO2_CROSS_MOD => self.vco_param.osc2_cross_mod.get(),
+
+            Params::UNISON_VOICES => self.unison_param.voices.get(),
+            Params::UNISON_DETUNE => self.unison_param.detune.get(),
+            Params::UNISON_SPREAD => self.unison_param.spread.get(),
+
+            Params::OUTPUT_PAN => self.output_param.pan.get(),
+            Params::OUTPUT_WIDTH => self.output_param.width.get(),
+            Params::OUTPUT_MONO => self.output_param.mono.get(),
+
+            Params::EXT_LEVEL => self.external_input_param.level.get(),
+            Params::EXT_TRIGGER => self.external_input_param.trigger.get(),
+            Params::EXT_THRESHOLD => self.external_input_param.threshold.get(),
+
+            Params::CHORUS_BYPASS => self.fx_param.chorus.bypass.get(),
+            Params::CHORUS_RATE => self.fx_param.chorus.rate.get(),
+            Params::CHORUS_DEPTH => self.fx_param.chorus.depth.get(),
+            Params::CHORUS_MIX => self.fx_param.chorus.mix.get(),
+            Params::DELAY_BYPASS => self.fx_param.delay.bypass.get(),
+            Params::DELAY_TIME => self.fx_param.delay.time.get(),
+            Params::DELAY_FEEDBACK => self.fx_param.delay.feedback.get(),
+            Params::DELAY_TONE => self.fx_param.delay.tone.get(),
+            Params::DELAY_MIX => self.fx_param.delay.mix.get(),
+            Params::REVERB_BYPASS => self.fx_param.reverb.bypass.get(),
+            Params::REVERB_SIZE => self.fx_param.reverb.size.get(),
+            Params::REVERB_DAMPING => self.fx_param.reverb.damping.get(),
+            Params::REVERB_MIX => self.fx_param.reverb.mix.get(),
+
+            Params::PRE_DRIVE_AMOUNT => self.drive_param.pre.amount.get(),
+            Params::PRE_DRIVE_SHAPE => self.drive_param.pre.shape.get(),
+            Params::POST_DRIVE_AMOUNT => self.drive_param.post.amount.get(),
+            Params::POST_DRIVE_SHAPE => self.drive_param.post.shape.get(),
+            Params::BIT_CRUSHER_BYPASS => self.bit_crusher_param.bypass.get(),
+            Params::BIT_CRUSHER_BITS => self.bit_crusher_param.bits.get(),
+            Params::BIT_CRUSHER_RATE => self.bit_crusher_param.rate.get(),
+
+            Params::MASTER_TUNE => self.tuning_param.master_tune.get(),
+
+            Params::ANALOG_AMOUNT => self.analog_param.amount.get(),
+
+            Params::VCO_PHASE_RESET => self.vco_param.phase_reset.get(),
+
+            Params::OVERSAMPLING_REALTIME => self.oversampling_param.realtime.get(),
+            Params::OVERSAMPLING_OFFLINE => self.oversampling_param.offline.get(),
+
             Params::UNKNOWN => (0.0),
         }
     }
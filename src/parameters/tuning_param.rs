@@ -0,0 +1,19 @@
+use vst::util::AtomicFloat;
+
+pub struct TuningParam {
+    pub master_tune: AtomicFloat,
+}
+
+impl TuningParam {
+    pub fn new(master_tune: f32) -> Self {
+        Self {
+            master_tune: AtomicFloat::new(master_tune),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            master_tune: AtomicFloat::new(0.5), // A4 = 440Hz
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use vst::util::AtomicFloat;
+
+pub struct UnisonParam {
+    pub voices: AtomicFloat,
+    pub detune: AtomicFloat,
+    pub spread: AtomicFloat,
+}
+
+impl UnisonParam {
+    pub fn new(voices: f32, detune: f32, spread: f32) -> Self {
+        Self {
+            voices: AtomicFloat::new(voices),
+            detune: AtomicFloat::new(detune),
+            spread: AtomicFloat::new(spread),
+        }
+    }
+
+    pub fn default() -> Self {
+        Self {
+            voices: AtomicFloat::new(0.0), // single oscillator
+            detune: AtomicFloat::new(0.2), // ±10 cents
+            spread: AtomicFloat::new(0.0), // mono
+        }
+    }
+}
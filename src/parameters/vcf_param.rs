@@ -5,24 +5,45 @@ pub struct VCFParam {
     pub cutoff_mod: AtomicFloat,
     pub k: AtomicFloat,
     pub kbd_follow: AtomicFloat,
+    pub compensation: AtomicFloat,
+    pub quality: AtomicFloat,
+    pub model: AtomicFloat,
+    pub svf_mode: AtomicFloat,
 }
 
 impl VCFParam {
-    pub fn new(cutoff: f32, cutoff_mod: f32, k: f32, kbd: f32) -> Self {
+    pub fn new(
+        cutoff: f32,
+        cutoff_mod: f32,
+        k: f32,
+        kbd: f32,
+        compensation: f32,
+        quality: f32,
+        model: f32,
+        svf_mode: f32,
+    ) -> Self {
         Self {
             cutoff: AtomicFloat::new(cutoff),
             cutoff_mod: AtomicFloat::new(cutoff_mod),
             k: AtomicFloat::new(k),
             kbd_follow: AtomicFloat::new(kbd),
+            compensation: AtomicFloat::new(compensation),
+            quality: AtomicFloat::new(quality),
+            model: AtomicFloat::new(model),
+            svf_mode: AtomicFloat::new(svf_mode),
         }
     }
 
     pub fn default() -> Self {
         Self {
-            cutoff: AtomicFloat::new(0.5), // 5000kHz
-            cutoff_mod: AtomicFloat::new(0.0),
+            cutoff: AtomicFloat::new(0.8),     // 5120Hz
+            cutoff_mod: AtomicFloat::new(0.5), // bipolar, 0.5 = no envelope
             k: AtomicFloat::new(0.0),
             kbd_follow: AtomicFloat::new(0.0),
+            compensation: AtomicFloat::new(0.5), // half
+            quality: AtomicFloat::new(0.5),      // zdf
+            model: AtomicFloat::new(0.0),        // diode ladder
+            svf_mode: AtomicFloat::new(0.0),     // lowpass
         }
     }
 }
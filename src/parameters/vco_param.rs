@@ -9,6 +9,15 @@ pub struct VCOParam {
     pub squ_rate: AtomicFloat,
     pub sub_rate: AtomicFloat,
     pub noise_rate: AtomicFloat,
+    pub pwm_source: AtomicFloat,
+    pub sub_mode: AtomicFloat,
+    pub osc2_level: AtomicFloat,
+    pub osc2_interval: AtomicFloat,
+    pub osc2_detune: AtomicFloat,
+    pub osc2_waveform: AtomicFloat,
+    pub osc2_sync: AtomicFloat,
+    pub osc2_cross_mod: AtomicFloat,
+    pub phase_reset: AtomicFloat,
 }
 
 impl VCOParam {
@@ -21,6 +30,15 @@ impl VCOParam {
         squ_rate: f32,
         sub_rate: f32,
         noise_rate: f32,
+        pwm_source: f32,
+        sub_mode: f32,
+        osc2_level: f32,
+        osc2_interval: f32,
+        osc2_detune: f32,
+        osc2_waveform: f32,
+        osc2_sync: f32,
+        osc2_cross_mod: f32,
+        phase_reset: f32,
     ) -> Self {
         Self {
             range: AtomicFloat::new(range),
@@ -31,6 +49,15 @@ impl VCOParam {
             squ_rate: AtomicFloat::new(squ_rate),
             sub_rate: AtomicFloat::new(sub_rate),
             noise_rate: AtomicFloat::new(noise_rate),
+            pwm_source: AtomicFloat::new(pwm_source),
+            sub_mode: AtomicFloat::new(sub_mode),
+            osc2_level: AtomicFloat::new(osc2_level),
+            osc2_interval: AtomicFloat::new(osc2_interval),
+            osc2_detune: AtomicFloat::new(osc2_detune),
+            osc2_waveform: AtomicFloat::new(osc2_waveform),
+            osc2_sync: AtomicFloat::new(osc2_sync),
+            osc2_cross_mod: AtomicFloat::new(osc2_cross_mod),
+            phase_reset: AtomicFloat::new(phase_reset),
         }
     }
 
@@ -44,6 +71,15 @@ impl VCOParam {
             squ_rate: AtomicFloat::new(0.0),
             sub_rate: AtomicFloat::new(0.0),
             noise_rate: AtomicFloat::new(0.0),
+            pwm_source: AtomicFloat::new(1.0),    // envelope
+            sub_mode: AtomicFloat::new(0.0),      // -1 oct square
+            osc2_level: AtomicFloat::new(0.0),    // off, the classic single oscillator
+            osc2_interval: AtomicFloat::new(0.5), // unison
+            osc2_detune: AtomicFloat::new(0.5),
+            osc2_waveform: AtomicFloat::new(0.0), // saw
+            osc2_sync: AtomicFloat::new(0.0),     // off
+            osc2_cross_mod: AtomicFloat::new(0.0),
+            phase_reset: AtomicFloat::new(0.0), // free-running
         }
     }
 }
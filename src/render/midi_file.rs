@@ -0,0 +1,371 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Microseconds per quarter note until the first tempo event, i.e. 120bpm.
+pub const DEFAULT_TEMPO: u32 = 500_000;
+
+#[derive(Debug)]
+pub enum MidiFileError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for MidiFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiFileError::Io(err) => write!(f, "{}", err),
+            MidiFileError::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for MidiFileError {
+    fn from(err: io::Error) -> Self {
+        MidiFileError::Io(err)
+    }
+}
+
+fn parse_error(message: &str) -> MidiFileError {
+    MidiFileError::Parse(message.to_string())
+}
+
+#[derive(Clone)]
+pub enum EventKind {
+    Midi([u8; 3]),  // one-data-byte messages are padded with a zero
+    SysEx(Vec<u8>), // including the leading F0
+    Tempo(u32),     // microseconds per quarter note
+}
+
+pub struct TrackEvent {
+    pub tick: u64, // absolute, from the start of the track
+    pub kind: EventKind,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data: data,
+            position: 0,
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], MidiFileError> {
+        let end = self.position + count;
+        if end > self.data.len() {
+            return Err(parse_error("unexpected end of file"));
+        }
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, MidiFileError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn peek(&self) -> Result<u8, MidiFileError> {
+        self.data
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| parse_error("unexpected end of file"))
+    }
+
+    fn u16(&mut self) -> Result<u16, MidiFileError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from(bytes[0]) << 8 | u16::from(bytes[1]))
+    }
+
+    fn u32(&mut self) -> Result<u32, MidiFileError> {
+        let bytes = self.bytes(4)?;
+        Ok(bytes.iter().fold(0, |value, &b| value << 8 | u32::from(b)))
+    }
+
+    // Variable-length quantity: 7 bits per byte, high bit set on all but the last.
+    fn vlq(&mut self) -> Result<u32, MidiFileError> {
+        let mut value: u32 = 0;
+        for _ in 0..4 {
+            let byte = self.byte()?;
+            value = value << 7 | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(parse_error("variable-length quantity too long"))
+    }
+
+    fn chunk(&mut self) -> Result<(&'a [u8], &'a [u8]), MidiFileError> {
+        let id = self.bytes(4)?;
+        let length = self.u32()? as usize;
+        Ok((id, self.bytes(length)?))
+    }
+}
+
+fn data_bytes(status: u8) -> usize {
+    match status & 0xF0 {
+        0xC0 | 0xD0 => 1,
+        _ => 2,
+    }
+}
+
+fn parse_track(data: &[u8]) -> Result<Vec<TrackEvent>, MidiFileError> {
+    let mut reader = Reader::new(data);
+    let mut events = Vec::new();
+    let mut tick: u64 = 0;
+    let mut running_status: Option<u8> = None;
+
+    while !reader.done() {
+        tick += u64::from(reader.vlq()?);
+        let status = if reader.peek()? & 0x80 != 0 {
+            reader.byte()?
+        } else {
+            running_status.ok_or_else(|| parse_error("data byte without running status"))?
+        };
+
+        // Meta and SysEx events cancel running status.
+        if status >= 0xF0 {
+            running_status = None;
+        }
+
+        match status {
+            0xFF => {
+                let meta = reader.byte()?;
+                let length = reader.vlq()? as usize;
+                let payload = reader.bytes(length)?;
+                match meta {
+                    0x2F => break, // end of track
+                    0x51 if length == 3 => {
+                        let tempo = payload
+                            .iter()
+                            .fold(0, |value, &b| value << 8 | u32::from(b));
+                        events.push(TrackEvent {
+                            tick: tick,
+                            kind: EventKind::Tempo(tempo),
+                        });
+                    }
+                    _ => (),
+                }
+            }
+            0xF0 | 0xF7 => {
+                let length = reader.vlq()? as usize;
+                let payload = reader.bytes(length)?;
+                // F7 events are escapes or continuation packets; only complete
+                // F0 messages are passed on.
+                if status == 0xF0 {
+                    let mut message = vec![0xF0];
+                    message.extend_from_slice(payload);
+                    events.push(TrackEvent {
+                        tick: tick,
+                        kind: EventKind::SysEx(message),
+                    });
+                }
+            }
+            0xF1..=0xFE => return Err(parse_error("unexpected system message")),
+            _ => {
+                running_status = Some(status);
+                let mut message = [status, 0, 0];
+                for byte in message.iter_mut().skip(1).take(data_bytes(status)) {
+                    *byte = reader.byte()?;
+                }
+                events.push(TrackEvent {
+                    tick: tick,
+                    kind: EventKind::Midi(message),
+                });
+            }
+        }
+    }
+    Ok(events)
+}
+
+// A Standard MIDI File, format 0 or 1, with metrical (ticks per quarter note)
+// timing.
+pub struct MidiFile {
+    pub format: u16,
+    pub ticks_per_quarter: u16,
+    pub tracks: Vec<Vec<TrackEvent>>,
+}
+
+impl MidiFile {
+    pub fn parse(data: &[u8]) -> Result<Self, MidiFileError> {
+        let mut reader = Reader::new(data);
+        let (id, header) = reader.chunk()?;
+        if id != b"MThd" || header.len() < 6 {
+            return Err(parse_error("not a Standard MIDI File"));
+        }
+        let mut header = Reader::new(header);
+        let format = header.u16()?;
+        let track_count = header.u16()?;
+        let division = header.u16()?;
+        if format > 1 {
+            return Err(parse_error("only format 0 and 1 files are supported"));
+        }
+        if division & 0x8000 != 0 {
+            return Err(parse_error("SMPTE time division is not supported"));
+        }
+
+        let mut tracks = Vec::with_capacity(usize::from(track_count));
+        while tracks.len() < usize::from(track_count) && !reader.done() {
+            let (id, data) = reader.chunk()?;
+            // Unknown chunk types must be skipped.
+            if id == b"MTrk" {
+                tracks.push(parse_track(data)?);
+            }
+        }
+
+        Ok(Self {
+            format: format,
+            ticks_per_quarter: division,
+            tracks: tracks,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MidiFileError> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    // All tracks merged into one list in tick order, keeping track order for
+    // events on the same tick.
+    pub fn merged(&self) -> Vec<&TrackEvent> {
+        let mut events: Vec<&TrackEvent> = self.tracks.iter().flatten().collect();
+        events.sort_by_key(|event| event.tick);
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(format: u16, division: u16, tracks: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"MThd\x00\x00\x00\x06".to_vec();
+        data.extend_from_slice(&format.to_be_bytes());
+        data.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+        data.extend_from_slice(&division.to_be_bytes());
+        for track in tracks {
+            data.extend_from_slice(b"MTrk");
+            data.extend_from_slice(&(track.len() as u32).to_be_bytes());
+            data.extend_from_slice(track);
+        }
+        data
+    }
+
+    type MidiEvent = (u64, Vec<u8>);
+
+    fn track(events: &[u8]) -> Vec<MidiEvent> {
+        let data = file(0, 480, &[events]);
+        let file = MidiFile::parse(&data).unwrap_or_else(|err| panic!("{}", err));
+        file.tracks[0]
+            .iter()
+            .map(|event| match &event.kind {
+                EventKind::Midi(data) => (event.tick, data.to_vec()),
+                EventKind::SysEx(data) => (event.tick, data.clone()),
+                EventKind::Tempo(tempo) => (event.tick, tempo.to_be_bytes().to_vec()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_variable_length_quantities() {
+        let cases: [(&[u8], u32); 6] = [
+            (&[0x00], 0),
+            (&[0x7F], 0x7F),
+            (&[0x81, 0x00], 0x80),
+            (&[0xFF, 0x7F], 0x3FFF),
+            (&[0x81, 0x80, 0x80, 0x00], 0x20_0000),
+            (&[0xFF, 0xFF, 0xFF, 0x7F], 0x0FFF_FFFF),
+        ];
+        for (bytes, value) in cases.iter() {
+            assert_eq!(Reader::new(bytes).vlq().unwrap(), *value);
+        }
+        assert!(Reader::new(&[0x80, 0x80, 0x80, 0x80, 0x00]).vlq().is_err());
+        assert!(Reader::new(&[0x80]).vlq().is_err());
+    }
+
+    #[test]
+    fn accumulates_delta_times_and_follows_running_status() {
+        let events = track(&[
+            0x00, 0x90, 0x3C, 0x64, // note on
+            0x81, 0x00, 0x3C, 0x00, // running status note off, 128 ticks later
+            0x10, 0xC0, 0x05, // program change, one data byte
+            0x00, 0x07, // running status program change
+            0x00, 0xFF, 0x2F, 0x00,
+        ]);
+        assert_eq!(
+            events,
+            vec![
+                (0, vec![0x90, 0x3C, 0x64]),
+                (128, vec![0x90, 0x3C, 0x00]),
+                (144, vec![0xC0, 0x05, 0x00]),
+                (144, vec![0xC0, 0x07, 0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn passes_complete_sysex_and_tempo_and_skips_escapes() {
+        let events = track(&[
+            0x00, 0xF0, 0x03, 0x7E, 0x01, 0xF7, // sysex
+            0x05, 0xF7, 0x02, 0xF8, 0xFA, // escaped realtime bytes
+            0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, // tempo, 500000us
+            0x00, 0xFF, 0x03, 0x02, b'h', b'i', // track name
+            0x00, 0xFF, 0x2F, 0x00,
+        ]);
+        assert_eq!(
+            events,
+            vec![
+                (0, vec![0xF0, 0x7E, 0x01, 0xF7]),
+                (5, 500_000u32.to_be_bytes().to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn meta_and_sysex_cancel_running_status() {
+        let after_meta = [
+            0x00, 0x90, 0x3C, 0x64, 0x00, 0xFF, 0x01, 0x00, 0x00, 0x3C, 0x00,
+        ];
+        assert!(MidiFile::parse(&file(0, 480, &[&after_meta])).is_err());
+        let after_sysex = [
+            0x00, 0x90, 0x3C, 0x64, 0x00, 0xF0, 0x01, 0xF7, 0x00, 0x3C, 0x00,
+        ];
+        assert!(MidiFile::parse(&file(0, 480, &[&after_sysex])).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_system_messages() {
+        for status in [0xF1, 0xF2, 0xF8, 0xFE].iter() {
+            let events = [0x00, *status, 0x00, 0x00, 0x00, 0xFF, 0x2F, 0x00];
+            assert!(MidiFile::parse(&file(0, 480, &[&events])).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_and_truncated_files() {
+        let end = [0x00, 0xFF, 0x2F, 0x00];
+        assert!(MidiFile::parse(&file(2, 480, &[&end])).is_err());
+        assert!(MidiFile::parse(&file(0, 0xE728, &[&end])).is_err());
+        assert!(MidiFile::parse(b"RIFF").is_err());
+        let mut truncated = file(0, 480, &[&[0x00, 0x90, 0x3C, 0x64]]);
+        truncated.pop();
+        assert!(MidiFile::parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn merges_tracks_in_tick_order() {
+        let first = [0x00, 0x90, 0x30, 0x64, 0x83, 0x60, 0x80, 0x30, 0x00];
+        let second = [0x81, 0x70, 0x91, 0x40, 0x64, 0x83, 0x60, 0x81, 0x40, 0x00];
+        let file = MidiFile::parse(&file(1, 480, &[&first, &second])).unwrap();
+        let ticks: Vec<u64> = file.merged().iter().map(|event| event.tick).collect();
+        assert_eq!(ticks, vec![0, 240, 480, 720]);
+    }
+}
@@ -0,0 +1,5 @@
+// Host-independent file I/O for rendering the synth offline.
+pub mod midi_file;
+pub mod player;
+pub mod preset;
+pub mod wav;
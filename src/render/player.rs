@@ -0,0 +1,232 @@
+use super::midi_file::{EventKind, MidiFile, DEFAULT_TEMPO};
+use crate::Sh101;
+
+const BLOCK_SIZE: usize = 256;
+const SILENCE: [f32; BLOCK_SIZE] = [0.0; BLOCK_SIZE];
+
+fn bpm(tempo: u32) -> f32 {
+    (60_000_000.0 / f64::from(tempo)) as f32
+}
+
+// Converts ticks to seconds across the tempo changes of a file. Each segment
+// starts at a tempo event and remembers the time it starts at.
+pub struct TempoMap {
+    ticks_per_quarter: f64,
+    segments: Vec<(u64, f64, u32)>, // start tick, start seconds, tempo
+}
+
+impl TempoMap {
+    pub fn new(file: &MidiFile) -> Self {
+        let ticks_per_quarter = f64::from(file.ticks_per_quarter.max(1));
+        let mut segments = vec![(0, 0.0, DEFAULT_TEMPO)];
+        for event in file.merged() {
+            if let EventKind::Tempo(tempo) = event.kind {
+                let seconds = Self::seconds_in(ticks_per_quarter, &segments, event.tick);
+                // A later tempo on the same tick replaces the earlier one.
+                if let Some(last) = segments.last_mut().filter(|last| last.0 == event.tick) {
+                    last.2 = tempo;
+                } else {
+                    segments.push((event.tick, seconds, tempo));
+                }
+            }
+        }
+        Self {
+            ticks_per_quarter: ticks_per_quarter,
+            segments: segments,
+        }
+    }
+
+    fn seconds_in(ticks_per_quarter: f64, segments: &[(u64, f64, u32)], tick: u64) -> f64 {
+        let index = segments
+            .iter()
+            .rposition(|segment| segment.0 <= tick)
+            .unwrap_or(0);
+        let (start, seconds, tempo) = segments[index];
+        seconds + (tick - start) as f64 * f64::from(tempo) / ticks_per_quarter / 1_000_000.0
+    }
+
+    pub fn seconds(&self, tick: u64) -> f64 {
+        Self::seconds_in(self.ticks_per_quarter, &self.segments, tick)
+    }
+
+    // Microseconds per quarter note in effect at `tick`.
+    pub fn tempo(&self, tick: u64) -> u32 {
+        self.segments
+            .iter()
+            .rev()
+            .find(|segment| segment.0 <= tick)
+            .map_or(DEFAULT_TEMPO, |segment| segment.2)
+    }
+}
+
+struct ScheduledEvent {
+    sample: usize,
+    kind: EventKind,
+}
+
+// Plays a MIDI file into the synth's event path, independent of any host.
+// Events are applied on the exact sample they fall on, and tempo events also
+// set the synth's tempo for the tempo-synced effects.
+pub struct Player {
+    events: Vec<ScheduledEvent>,
+    next: usize,
+    position: usize,
+}
+
+impl Player {
+    pub fn new(file: &MidiFile, sample_rate: f32) -> Self {
+        let tempo_map = TempoMap::new(file);
+        let initial = ScheduledEvent {
+            sample: 0,
+            kind: EventKind::Tempo(tempo_map.tempo(0)),
+        };
+        let events = file.merged().into_iter().map(|event| ScheduledEvent {
+            sample: (tempo_map.seconds(event.tick) * f64::from(sample_rate)).round() as usize,
+            kind: event.kind.clone(),
+        });
+
+        Self {
+            events: std::iter::once(initial).chain(events).collect(),
+            next: 0,
+            position: 0,
+        }
+    }
+
+    // Sample position of the last event; renders usually add a release tail.
+    pub fn length(&self) -> usize {
+        self.events.last().map_or(0, |event| event.sample)
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    // Back to the start of the file. The synth itself is not reset.
+    pub fn rewind(&mut self) {
+        self.next = 0;
+        self.position = 0;
+    }
+
+    fn dispatch(synth: &mut Sh101, kind: &EventKind) {
+        match kind {
+            EventKind::Midi(data) => synth.process_midi_event(*data),
+            EventKind::SysEx(data) => synth.process_sysex(data),
+            EventKind::Tempo(tempo) => synth.set_tempo(bpm(*tempo)),
+        }
+    }
+
+    // Renders the next `left.len()` samples, cutting blocks short at each
+    // event so it lands on its own sample.
+    pub fn render(&mut self, synth: &mut Sh101, left: &mut [f32], right: &mut [f32]) {
+        let length = left.len().min(right.len());
+        let mut done = 0;
+        while done < length {
+            while let Some(event) = self.events.get(self.next) {
+                if event.sample > self.position {
+                    break;
+                }
+                Self::dispatch(synth, &event.kind);
+                self.next += 1;
+            }
+
+            let until_event = self
+                .events
+                .get(self.next)
+                .map_or(usize::MAX, |event| event.sample - self.position);
+            let frames = until_event.min(BLOCK_SIZE).min(length - done);
+            synth.process_block(
+                &SILENCE[..frames],
+                &SILENCE[..frames],
+                &mut left[done..done + frames],
+                &mut right[done..done + frames],
+            );
+            done += frames;
+            self.position += frames;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vst::plugin::Plugin;
+
+    const END_OF_TRACK: [u8; 4] = [0x00, 0xFF, 0x2F, 0x00];
+
+    fn file(events: &[u8]) -> MidiFile {
+        let mut data = b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x01\xE0".to_vec(); // 480 ticks
+        data.extend_from_slice(b"MTrk");
+        data.extend_from_slice(&(events.len() as u32 + 4).to_be_bytes());
+        data.extend_from_slice(events);
+        data.extend_from_slice(&END_OF_TRACK);
+        MidiFile::parse(&data).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn assert_seconds(tempo_map: &TempoMap, tick: u64, seconds: f64) {
+        let actual = tempo_map.seconds(tick);
+        assert!(
+            (actual - seconds).abs() < 1.0e-9,
+            "tick {} at {}s instead of {}s",
+            tick,
+            actual,
+            seconds
+        );
+    }
+
+    #[test]
+    fn defaults_to_120_bpm() {
+        let tempo_map = TempoMap::new(&file(&[0x00, 0x90, 0x3C, 0x64]));
+        assert_eq!(tempo_map.tempo(0), DEFAULT_TEMPO);
+        assert_seconds(&tempo_map, 480, 0.5);
+        assert_seconds(&tempo_map, 1200, 1.25);
+    }
+
+    #[test]
+    fn converts_ticks_across_tempo_changes() {
+        let tempo_map = TempoMap::new(&file(&[
+            0x83, 0x60, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, // 60bpm at 480
+            0x83, 0x60, 0xFF, 0x51, 0x03, 0x03, 0xD0, 0x90, // 240bpm at 960...
+            0x00, 0xFF, 0x51, 0x03, 0x0B, 0x71, 0xB0, // ...replaced by 80bpm
+        ]));
+        assert_seconds(&tempo_map, 240, 0.25);
+        assert_seconds(&tempo_map, 480, 0.5);
+        assert_seconds(&tempo_map, 720, 1.0);
+        assert_seconds(&tempo_map, 960, 1.5);
+        assert_seconds(&tempo_map, 1440, 2.25);
+        assert_eq!(tempo_map.tempo(479), DEFAULT_TEMPO);
+        assert_eq!(tempo_map.tempo(480), 1_000_000);
+        assert_eq!(tempo_map.tempo(960), 750_000);
+    }
+
+    #[test]
+    fn applies_events_on_their_exact_sample() {
+        // At 120bpm and 96kHz a tick lasts exactly 100 samples.
+        let midi = file(&[0x03, 0x90, 0x24, 0x7F, 0x02, 0x80, 0x24, 0x00]);
+        let mut synth = Sh101::standalone();
+        synth.set_offline(true);
+        synth.params.oversampling_param.offline.set(0.0); // no decimator latency
+        synth.set_sample_rate(96000.0);
+        let mut player = Player::new(&midi, 96000.0);
+        assert_eq!(player.length(), 500);
+
+        // The second call starts at sample 10, so the note on falls inside
+        // it after one full block.
+        let mut left = vec![0.0; 1000];
+        let mut right = vec![0.0; 1000];
+        let mut start = 0;
+        for &chunk in [10, 600, 390].iter() {
+            let end = start + chunk;
+            player.render(&mut synth, &mut left[start..end], &mut right[start..end]);
+            start = end;
+        }
+        assert_eq!(player.position(), 1000);
+        assert!(player.is_finished());
+
+        let first_sound = left.iter().position(|&sample| sample != 0.0);
+        assert_eq!(first_sound, Some(300));
+    }
+}
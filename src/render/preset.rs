@@ -0,0 +1,33 @@
+use vst::plugin::PluginParameters;
+
+// Applies a text preset: one `Parameter Name = value` line per parameter, with
+// the name as the host displays it and the value normalised to 0..1. Blank
+// lines and lines starting with `#` are ignored, and unlisted parameters keep
+// their defaults.
+pub fn apply(params: &dyn PluginParameters, num_parameters: i32, text: &str) -> Result<(), String> {
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: &str| format!("line {}: {}", number + 1, message);
+
+        let mut parts = line.splitn(2, '=');
+        let name = parts.next().unwrap_or("").trim();
+        let value: f32 = parts
+            .next()
+            .ok_or_else(|| error("expected `name = value`"))?
+            .trim()
+            .parse()
+            .map_err(|_| error("value is not a number"))?;
+        if !(0.0..=1.0).contains(&value) {
+            return Err(error("value must be between 0 and 1"));
+        }
+
+        let index = (0..num_parameters)
+            .find(|&index| params.get_parameter_name(index).eq_ignore_ascii_case(name))
+            .ok_or_else(|| error(&format!("unknown parameter `{}`", name)))?;
+        params.set_parameter(index, value);
+    }
+    Ok(())
+}
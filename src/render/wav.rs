@@ -0,0 +1,410 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE; // the real tag is the start of the sub-format GUID
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SampleFormat {
+    PCM_16,
+    PCM_24,
+    FLOAT_32,
+}
+
+impl SampleFormat {
+    fn bits(&self) -> u16 {
+        match self {
+            Self::PCM_16 => 16,
+            Self::PCM_24 => 24,
+            Self::FLOAT_32 => 32,
+        }
+    }
+
+    fn tag(&self) -> u16 {
+        match self {
+            Self::FLOAT_32 => FORMAT_FLOAT,
+            _ => FORMAT_PCM,
+        }
+    }
+
+    fn from_header(tag: u16, bits: u16) -> Option<Self> {
+        match (tag, bits) {
+            (FORMAT_PCM, 16) => Some(Self::PCM_16),
+            (FORMAT_PCM, 24) => Some(Self::PCM_24),
+            (FORMAT_FLOAT, 32) => Some(Self::FLOAT_32),
+            _ => None,
+        }
+    }
+
+    // Integer formats are scaled by their largest positive value both ways, so
+    // a write followed by a read returns the quantised input.
+    fn scale(&self) -> f32 {
+        match self {
+            Self::PCM_16 => 32767.0,
+            Self::PCM_24 => 8388607.0,
+            Self::FLOAT_32 => 1.0,
+        }
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+// Writes interleaved samples in `format`. Every channel must hold the same
+// number of samples; integer formats clip values outside -1..1.
+pub fn write_to<W: Write>(
+    out: &mut W,
+    format: SampleFormat,
+    sample_rate: u32,
+    channels: &[&[f32]],
+) -> io::Result<()> {
+    let frames = channels.first().map_or(0, |channel| channel.len());
+    let channel_count = u16::try_from(channels.len()).map_err(|_| invalid("too many channels"))?;
+    let block_align = u32::from(channel_count) * u32::from(format.bits() / 8);
+    let block_align = u16::try_from(block_align).map_err(|_| invalid("too many channels"))?;
+    let byte_rate = sample_rate
+        .checked_mul(u32::from(block_align))
+        .ok_or_else(|| invalid("sample rate too high"))?;
+
+    // Non-PCM formats need the cbSize field and a fact chunk with the frame
+    // count.
+    let float = format.tag() != FORMAT_PCM;
+    let fmt_size: u64 = if float { 18 } else { 16 };
+    let fact_size: u64 = if float { 12 } else { 0 };
+    let data_size = frames as u64 * u64::from(block_align);
+    let riff_size = 4 + 8 + fmt_size + fact_size + 8 + data_size + data_size % 2;
+    if riff_size > u64::from(u32::MAX) {
+        return Err(invalid("too much audio for a WAV file"));
+    }
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&(riff_size as u32).to_le_bytes())?;
+    out.write_all(b"WAVE")?;
+
+    out.write_all(b"fmt ")?;
+    out.write_all(&(fmt_size as u32).to_le_bytes())?;
+    out.write_all(&format.tag().to_le_bytes())?;
+    out.write_all(&channel_count.to_le_bytes())?;
+    out.write_all(&sample_rate.to_le_bytes())?;
+    out.write_all(&byte_rate.to_le_bytes())?;
+    out.write_all(&block_align.to_le_bytes())?;
+    out.write_all(&format.bits().to_le_bytes())?;
+    if float {
+        out.write_all(&0u16.to_le_bytes())?;
+        out.write_all(b"fact")?;
+        out.write_all(&4u32.to_le_bytes())?;
+        out.write_all(&(frames as u32).to_le_bytes())?;
+    }
+
+    out.write_all(b"data")?;
+    out.write_all(&(data_size as u32).to_le_bytes())?;
+    let scale = format.scale();
+    for frame in 0..frames {
+        for channel in channels {
+            let sample = channel[frame];
+            match format {
+                SampleFormat::PCM_16 => {
+                    let value = (sample.max(-1.0).min(1.0) * scale).round() as i16;
+                    out.write_all(&value.to_le_bytes())?;
+                }
+                SampleFormat::PCM_24 => {
+                    let value = (sample.max(-1.0).min(1.0) * scale).round() as i32;
+                    out.write_all(&value.to_le_bytes()[..3])?;
+                }
+                SampleFormat::FLOAT_32 => out.write_all(&sample.to_le_bytes())?,
+            }
+        }
+    }
+    // Chunks are padded to an even length.
+    if data_size % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+    Ok(())
+}
+
+pub fn write<P: AsRef<Path>>(
+    path: P,
+    format: SampleFormat,
+    sample_rate: u32,
+    channels: &[&[f32]],
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_to(&mut out, format, sample_rate, channels)?;
+    out.flush()
+}
+
+// A decoded WAV file, one sample vector per channel.
+pub struct Wav {
+    pub format: SampleFormat,
+    pub sample_rate: u32,
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl Wav {
+    // Reads the formats `write` produces, including their WAVE_FORMAT_EXTENSIBLE
+    // variants; other chunks are skipped.
+    pub fn parse(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+            return Err(invalid("not a WAV file"));
+        }
+
+        let mut header: Option<(SampleFormat, u16, u32)> = None;
+        let mut position = 12;
+        while position + 8 <= data.len() {
+            let id = &data[position..position + 4];
+            let mut size = [0; 4];
+            size.copy_from_slice(&data[position + 4..position + 8]);
+            let size = u32::from_le_bytes(size) as usize;
+            if position + 8 + size > data.len() {
+                return Err(invalid("truncated chunk"));
+            }
+            let body = &data[position + 8..position + 8 + size];
+            let u16_at = |offset: usize| u16::from_le_bytes([body[offset], body[offset + 1]]);
+
+            if id == b"fmt " {
+                if body.len() < 16 {
+                    return Err(invalid("truncated fmt chunk"));
+                }
+                let mut tag = u16_at(0);
+                if tag == FORMAT_EXTENSIBLE && body.len() >= 26 {
+                    tag = u16_at(24);
+                }
+                let channels = u16_at(2);
+                let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                let format = SampleFormat::from_header(tag, u16_at(14))
+                    .ok_or_else(|| invalid("unsupported sample format"))?;
+                if channels == 0 {
+                    return Err(invalid("no channels"));
+                }
+                header = Some((format, channels, sample_rate));
+            } else if id == b"data" {
+                let (format, channels, sample_rate) =
+                    header.ok_or_else(|| invalid("data chunk before fmt chunk"))?;
+                return Ok(Self::decode(format, channels, sample_rate, body));
+            }
+            position += 8 + size + size % 2;
+        }
+        Err(invalid("no data chunk"))
+    }
+
+    fn decode(format: SampleFormat, channels: u16, sample_rate: u32, data: &[u8]) -> Self {
+        let width = usize::from(format.bits() / 8);
+        let scale = format.scale();
+        let mut decoded = vec![Vec::new(); usize::from(channels)];
+        for (index, bytes) in data.chunks_exact(width).enumerate() {
+            let sample = match format {
+                SampleFormat::PCM_16 => f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / scale,
+                // Shifted up into an i32 and back down to sign-extend.
+                SampleFormat::PCM_24 => {
+                    (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / scale
+                }
+                SampleFormat::FLOAT_32 => {
+                    f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+                }
+            };
+            decoded[index % usize::from(channels)].push(sample);
+        }
+        // A trailing partial frame is dropped so all channels line up.
+        let frames = decoded.iter().map(Vec::len).min().unwrap_or(0);
+        for channel in decoded.iter_mut() {
+            channel.truncate(frames);
+        }
+
+        Self {
+            format: format,
+            sample_rate: sample_rate,
+            channels: decoded,
+        }
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    pub fn frames(&self) -> usize {
+        self.channels.first().map_or(0, Vec::len)
+    }
+}
+
+// Largest absolute sample difference, for comparing a render against a golden
+// reference. Signals of different lengths never match.
+pub fn max_difference(rendered: &[f32], reference: &[f32]) -> f32 {
+    if rendered.len() != reference.len() {
+        return f32::INFINITY;
+    }
+    rendered
+        .iter()
+        .zip(reference.iter())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sh101;
+    use std::path::PathBuf;
+    use vst::plugin::Plugin;
+
+    const GOLDEN_FRAMES: usize = 4096;
+    const GOLDEN_TOLERANCE: f32 = 1.0e-4;
+
+    fn encode(format: SampleFormat, channels: &[&[f32]]) -> Vec<u8> {
+        let mut data = Vec::new();
+        write_to(&mut data, format, 48000, channels).unwrap();
+        data
+    }
+
+    fn ramp() -> Vec<f32> {
+        (0..101).map(|i| i as f32 / 50.0 - 1.0).collect()
+    }
+
+    fn round_trip(format: SampleFormat, step: f32) {
+        let left = ramp();
+        let right: Vec<f32> = left.iter().map(|x| -0.5 * x).collect();
+        let wav = Wav::parse(&encode(format, &[&left, &right])).unwrap();
+        assert_eq!(wav.format, format);
+        assert_eq!(wav.sample_rate, 48000);
+        assert_eq!(wav.frames(), left.len());
+        assert!(max_difference(&wav.channels[0], &left) <= step / 2.0);
+        assert!(max_difference(&wav.channels[1], &right) <= step / 2.0);
+    }
+
+    #[test]
+    fn round_trips_every_format() {
+        round_trip(SampleFormat::PCM_16, 1.0 / 32767.0);
+        round_trip(SampleFormat::PCM_24, 1.0 / 8388607.0);
+        round_trip(SampleFormat::FLOAT_32, 0.0);
+    }
+
+    #[test]
+    fn clips_integer_formats_at_full_scale() {
+        let samples = [-1.5, -1.0, 1.0, 1.5];
+        for format in [SampleFormat::PCM_16, SampleFormat::PCM_24].iter() {
+            let wav = Wav::parse(&encode(*format, &[&samples])).unwrap();
+            assert_eq!(wav.channels[0], vec![-1.0, -1.0, 1.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn reads_the_most_negative_24_bit_sample() {
+        let mut data = encode(SampleFormat::PCM_24, &[&[0.0]]);
+        // The single sample sits just before the pad byte.
+        let start = data.len() - 4;
+        data[start..start + 3].copy_from_slice(&[0x00, 0x00, 0x80]);
+        let wav = Wav::parse(&data).unwrap();
+        assert_eq!(wav.channels[0], vec![-8388608.0 / 8388607.0]);
+    }
+
+    #[test]
+    fn writes_float_with_cb_size_and_fact_chunk() {
+        let data = encode(SampleFormat::FLOAT_32, &[&[0.25, -0.25, 0.5]]);
+        assert_eq!(&data[12..16], b"fmt ");
+        assert_eq!(&data[16..20], &18u32.to_le_bytes());
+        assert_eq!(&data[36..38], &0u16.to_le_bytes());
+        assert_eq!(&data[38..42], b"fact");
+        assert_eq!(&data[46..50], &3u32.to_le_bytes());
+        assert_eq!(&data[4..8], &(data.len() as u32 - 8).to_le_bytes());
+    }
+
+    #[test]
+    fn pads_odd_data_chunks() {
+        let data = encode(SampleFormat::PCM_24, &[&[0.1, 0.2, 0.3]]);
+        assert_eq!(data.len() % 2, 0);
+        assert_eq!(&data[4..8], &(data.len() as u32 - 8).to_le_bytes());
+        assert_eq!(Wav::parse(&data).unwrap().frames(), 3);
+    }
+
+    #[test]
+    fn reads_extensible_headers() {
+        let samples = [0.5, -0.25];
+        let plain = encode(SampleFormat::FLOAT_32, &[&samples]);
+        // Rebuild the fmt chunk as WAVE_FORMAT_EXTENSIBLE with the float
+        // sub-format GUID.
+        let mut fmt = plain[20..36].to_vec();
+        fmt[0..2].copy_from_slice(&FORMAT_EXTENSIBLE.to_le_bytes());
+        fmt.extend_from_slice(&22u16.to_le_bytes()); // cbSize
+        fmt.extend_from_slice(&32u16.to_le_bytes()); // valid bits
+        fmt.extend_from_slice(&4u32.to_le_bytes()); // channel mask
+        fmt.extend_from_slice(&FORMAT_FLOAT.to_le_bytes());
+        fmt.extend_from_slice(&[
+            0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+        ]);
+        let mut data = b"RIFF\x00\x00\x00\x00WAVEfmt ".to_vec();
+        data.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        data.extend_from_slice(&fmt);
+        data.extend_from_slice(&plain[38..]); // fact and data chunks
+        let riff_size = data.len() as u32 - 8;
+        data[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+        let wav = Wav::parse(&data).unwrap();
+        assert_eq!(wav.format, SampleFormat::FLOAT_32);
+        assert_eq!(wav.channels, vec![samples.to_vec()]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_unsupported_files() {
+        let data = encode(SampleFormat::PCM_16, &[&ramp()]);
+        assert!(Wav::parse(&data[..data.len() - 1]).is_err());
+        assert!(Wav::parse(&data[..30]).is_err());
+        assert!(Wav::parse(&data[..8]).is_err());
+
+        let mut eight_bit = data.clone();
+        eight_bit[34..36].copy_from_slice(&8u16.to_le_bytes());
+        assert!(Wav::parse(&eight_bit).is_err());
+    }
+
+    #[test]
+    fn compares_lengths_and_samples() {
+        assert_eq!(max_difference(&[0.5, 0.25], &[0.5, 0.0]), 0.25);
+        assert_eq!(max_difference(&[0.5], &[0.5, 0.0]), f32::INFINITY);
+    }
+
+    fn golden_path() -> PathBuf {
+        Path::new(file!()).with_file_name("testdata/seeded_note.wav")
+    }
+
+    // One low note with a fixed seed, at default settings.
+    fn render_seeded_note() -> (Vec<f32>, Vec<f32>) {
+        let mut synth = Sh101::standalone();
+        synth.set_offline(true);
+        synth.set_sample_rate(44100.0);
+        synth.set_seed(1);
+        synth.process_midi_event([0x90, 36, 100]);
+
+        let silence = vec![0.0; GOLDEN_FRAMES];
+        let mut left = vec![0.0; GOLDEN_FRAMES];
+        let mut right = vec![0.0; GOLDEN_FRAMES];
+        synth.process_block(&silence, &silence, &mut left, &mut right);
+        (left, right)
+    }
+
+    #[test]
+    fn seeded_render_matches_golden_reference() {
+        let golden = Wav::read(golden_path()).unwrap();
+        let (left, right) = render_seeded_note();
+        assert_eq!(golden.sample_rate, 44100);
+        assert!(max_difference(&left, &golden.channels[0]) < GOLDEN_TOLERANCE);
+        assert!(max_difference(&right, &golden.channels[1]) < GOLDEN_TOLERANCE);
+    }
+
+    // Rewrites the reference after an intended change to the sound:
+    // cargo test update_golden_reference -- --ignored
+    #[test]
+    #[ignore]
+    fn update_golden_reference() {
+        let (left, right) = render_seeded_note();
+        write(
+            golden_path(),
+            SampleFormat::FLOAT_32,
+            44100,
+            &[&left, &right],
+        )
+        .unwrap();
+    }
+}